@@ -0,0 +1,26 @@
+#version 150 core
+
+// overlay starts to show this far from the center towards the edge
+#define BLEND_FROM 0.4
+
+uniform sampler2D tile_sheet;
+
+in vec2 uv;
+in vec2 overlay_uv;
+in float shade;
+in float edge_weight;
+flat in float blended;
+flat in float fog;
+flat in vec3 light;
+
+out vec4 color;
+
+void main() {
+  vec4 texel = texture(tile_sheet, uv);
+  if (blended > 0.5) {
+    vec4 overlay = texture(tile_sheet, overlay_uv);
+    texel.rgb = mix(texel.rgb, overlay.rgb, overlay.a * smoothstep(BLEND_FROM, 1.0, edge_weight));
+  }
+  // unexplored tiles are plain black
+  color = vec4(texel.rgb * min(light, vec3(1.0)) * shade * fog, texel.a);
+}
@@ -0,0 +1,118 @@
+#version 150 core
+
+#define MAX_CORNERS 6
+#define SIDE_SHADE 0.6
+
+layout (points) in;
+// top fan of 6 triangles and up to 6 side quads
+layout (triangle_strip, max_vertices = 42) out;
+
+layout (std140) uniform CameraProperties {
+  mat4 projection_matrix;
+  mat4 view_matrix;
+};
+
+layout (std140) uniform TileMapArguments {
+  vec2 tile_size;
+  vec2 texture_size;
+  mat2 basis;
+  vec2 corners[MAX_CORNERS];
+  int corner_count;
+  float elevation_step;
+  int texels_per_tile;
+  vec3 light_color;
+  float light_intensity;
+};
+
+// texel k of tile i is at (i % width, (i / width) * texels_per_tile + k)
+uniform sampler2D tile_props;
+uniform float tile_props_width;
+
+flat in ivec4 tile[];
+
+out vec2 uv;
+out vec2 overlay_uv;
+out float shade;
+// 0 in the center, 1 on the edge of the triangle
+out float edge_weight;
+// 1 when the edge of the triangle borders the overlay kind
+flat out float blended;
+// fog of war of the player, 0 for unexplored tiles, darker for the ones out of sight
+flat out float fog;
+// sky light with the point lights reaching the tile added
+flat out vec3 light;
+
+vec4 tile_props_at(int index, int texel) {
+  int width = int(tile_props_width);
+  return texelFetch(tile_props, ivec2(index % width, (index / width) * texels_per_tile + texel), 0);
+}
+
+vec2 low;
+vec2 high;
+vec4 sprite;
+vec4 transition;
+
+// sprite is stretched over the bounding box of the outline
+vec2 sprite_uv(vec2 offset) {
+  vec2 t = (offset - low) / (high - low);
+  return sprite.xy + sprite.zw * vec2(t.x, 1.0 - t.y);
+}
+
+void emit(vec2 at, vec2 texture_at, float light, float weight) {
+  uv = sprite_uv(texture_at);
+  overlay_uv = transition.xy + uv - sprite.xy;
+  shade = light;
+  edge_weight = weight;
+  gl_Position = projection_matrix * view_matrix * vec4(at, 0.0, 1.0);
+  EmitVertex();
+}
+
+// Fan of triangles from the center to every edge. Corners are given in basis units
+// by the topology of the map, so hex, square and isometric tiles share this shader.
+// Raised tiles get a darker side face under every edge facing the viewer.
+void main() {
+  int index = tile[0].z;
+  sprite = tile_props_at(index, 0);
+  transition = tile_props_at(index, 1);
+  vec4 state = tile_props_at(index, 2);
+  fog = state.x;
+  light = light_color * light_intensity + state.yzw;
+  int mask = int(transition.z);
+  float lift = float(tile[0].w) * elevation_step;
+  vec2 ground = basis * vec2(tile[0].xy);
+  vec2 center = ground + vec2(0.0, lift);
+  low = vec2(1e9);
+  high = vec2(-1e9);
+  for (int i = 0; i < corner_count; i++) {
+    vec2 c = basis * corners[i];
+    low = min(low, c);
+    high = max(high, c);
+  }
+
+  if (lift > 0.0) {
+    for (int i = 0; i < corner_count; i++) {
+      vec2 a = basis * corners[i];
+      vec2 b = basis * corners[(i + 1) % corner_count];
+      // only edges on the lower half of the outline face the viewer
+      if (a.y + b.y >= 0.0) {
+        continue;
+      }
+      blended = 0.0;
+      emit(center + a, a, SIDE_SHADE, 0.0);
+      emit(center + b, b, SIDE_SHADE, 0.0);
+      emit(ground + a, a, SIDE_SHADE, 0.0);
+      emit(ground + b, b, SIDE_SHADE, 0.0);
+      EndPrimitive();
+    }
+  }
+
+  for (int i = 0; i < corner_count; i++) {
+    vec2 a = basis * corners[i];
+    vec2 b = basis * corners[(i + 1) % corner_count];
+    blended = float((mask >> i) & 1);
+    emit(center, vec2(0.0), 1.0, 0.0);
+    emit(center + a, a, 1.0, 1.0);
+    emit(center + b, b, 1.0, 1.0);
+    EndPrimitive();
+  }
+}
@@ -0,0 +1,12 @@
+#version 150 core
+
+// x, y of the tile, its index into the tile props texture and its elevation
+in ivec4 position_id;
+
+flat out ivec4 tile;
+
+// tiles are points, the geometry shader builds their outlines
+void main() {
+  tile = position_id;
+  gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+}
@@ -1,41 +1,256 @@
-
+use std::collections::HashMap;
 use amethyst::{
   prelude::{ World },
+  ecs::{ Join, Read, ReadExpect, System, Write, WriteStorage },
   renderer::{
     PngFormat,
+    Sprite,
+    SpriteRender,
     Texture,
     TextureMetadata,
     SpriteSheetHandle,
     SpriteSheetFormat,
     SpriteSheet,
     TextureHandle
-  }, 
-  assets::{ SimpleFormat, Loader, AssetStorage }
+  },
+  assets::{ Asset, Handle, Loader, AssetStorage, ProgressCounter, Completion }
 };
+use crate::pawn::sprites::SpriteCollection;
+use crate::rendering::tile_map::tile_map::TextureInfo;
+
+const PLACEHOLDER_COLOR: [f32; 4] = [1.0, 0.0, 1.0, 1.0];
+const PLACEHOLDER_SPRITE_SIZE: u32 = 128;
+
+struct Tracked<A: Asset> {
+  path: String,
+  handle: Handle<A>,
+  progress: ProgressCounter,
+  reported: bool,
+}
+
+impl<A: Asset> Tracked<A> {
+  fn is_complete(&self) -> bool {
+    self.progress.is_complete()
+  }
+
+  fn is_failed(&self) -> bool {
+    match self.progress.complete() {
+      Completion::Failed => true,
+      _ => false
+    }
+  }
+}
+
+// Every load issued through this module is tracked here, each asset with its own progress,
+// so failures are reported with paths and replaced by magenta placeholders.
+#[derive(Default)]
+pub struct AssetRegistry {
+  textures: Vec<Tracked<Texture>>,
+  sprite_sheets: Vec<Tracked<SpriteSheet>>,
+  failed_textures: HashMap<u32, String>,
+  failed_sprite_sheets: HashMap<u32, String>,
+  placeholder_texture: Option<TextureHandle>,
+  placeholder_sprite_sheet: Option<SpriteSheetHandle>,
+}
+
+impl AssetRegistry {
+  pub fn is_complete(&self) -> bool {
+    self.textures.iter().all(|t| t.is_complete()) &&
+      self.sprite_sheets.iter().all(|t| t.is_complete())
+  }
+
+  // (completed, total)
+  pub fn progress(&self) -> (usize, usize) {
+    let done = self.textures.iter().filter(|t| t.is_complete()).count() +
+      self.sprite_sheets.iter().filter(|t| t.is_complete()).count();
+    (done, self.textures.len() + self.sprite_sheets.len())
+  }
+
+  pub fn failures(&self) -> Vec<String> {
+    self.textures.iter().filter(|t| t.is_failed()).map(|t| t.path.clone())
+      .chain(self.sprite_sheets.iter().filter(|t| t.is_failed()).map(|t| t.path.clone()))
+      .collect()
+  }
+
+  pub fn texture_path(&self, handle: &TextureHandle) -> Option<&str> {
+    self.textures.iter()
+      .find(|t| t.handle.id() == handle.id())
+      .map(|t| t.path.as_str())
+  }
+
+  // already requested assets are shared, failed ones are given as their placeholder
+  fn loaded_texture(&self, path: &str) -> Option<TextureHandle> {
+    self.textures.iter()
+      .find(|t| t.path == path)
+      .map(|t| match (&self.placeholder_texture, t.reported) {
+        (Some(placeholder), true) => placeholder.clone(),
+        _ => t.handle.clone()
+      })
+  }
+
+  fn loaded_sprite_sheet(&self, path: &str) -> Option<SpriteSheetHandle> {
+    self.sprite_sheets.iter()
+      .find(|t| t.path == path)
+      .map(|t| match (&self.placeholder_sprite_sheet, t.reported) {
+        (Some(placeholder), true) => placeholder.clone(),
+        _ => t.handle.clone()
+      })
+  }
+
+  fn placeholder_texture(&mut self, loader: &Loader, storage: &AssetStorage<Texture>) -> TextureHandle {
+    self.placeholder_texture
+      .get_or_insert_with(|| loader.load_from_data(PLACEHOLDER_COLOR.into(), (), storage))
+      .clone()
+  }
 
-pub fn load_png_texture(world: &mut World, path: String) -> TextureHandle 
+  fn placeholder_sprite_sheet(
+    &mut self,
+    loader: &Loader,
+    textures: &AssetStorage<Texture>,
+    sheets: &AssetStorage<SpriteSheet>
+  ) -> SpriteSheetHandle {
+    if let Some(h) = &self.placeholder_sprite_sheet {
+      return h.clone();
+    }
+    let size = PLACEHOLDER_SPRITE_SIZE;
+    let sheet = SpriteSheet {
+      texture: self.placeholder_texture(loader, textures),
+      sprites: vec![Sprite::from_pixel_values(size, size, size, size, 0, 0, [0.0, 0.0])]
+    };
+    let handle = loader.load_from_data(sheet, (), sheets);
+    self.placeholder_sprite_sheet = Some(handle.clone());
+    handle
+  }
+
+  // returns true if new failures were found
+  fn collect_failures(&mut self) -> bool {
+    let mut found = false;
+    for t in self.textures.iter_mut().filter(|t| !t.reported && t.is_failed()) {
+      println!("texture {} failed to load, placeholder is used instead", t.path);
+      t.reported = true;
+      self.failed_textures.insert(t.handle.id(), t.path.clone());
+      found = true;
+    }
+    for t in self.sprite_sheets.iter_mut().filter(|t| !t.reported && t.is_failed()) {
+      println!("sprite sheet {} failed to load, placeholder is used instead", t.path);
+      t.reported = true;
+      self.failed_sprite_sheets.insert(t.handle.id(), t.path.clone());
+      found = true;
+    }
+    found
+  }
+}
+
+pub fn load_png_texture(world: &mut World, path: String) -> TextureHandle
 {
+  if let Some(handle) = world.res.entry::<AssetRegistry>().or_insert_with(AssetRegistry::default).loaded_texture(&path) {
+    return handle;
+  }
+  let mut progress = ProgressCounter::new();
   let texture_handle = {
     let loader = world.read_resource::<Loader>();
     let texture_storage = world.read_resource::<AssetStorage<Texture>>();
     loader.load(
-      path,
+      path.clone(),
       PngFormat,
       TextureMetadata::srgb_scale(),
-      (),
+      &mut progress,
       &texture_storage
     )
   };
+  world.res.entry::<AssetRegistry>().or_insert_with(AssetRegistry::default)
+    .textures.push(Tracked {
+      path,
+      handle: texture_handle.clone(),
+      progress,
+      reported: false
+    });
   texture_handle
 }
 pub fn load_ss_asset(world: &mut World, path: String, th: TextureHandle) -> SpriteSheetHandle {
-  let loader = world.read_resource::<Loader>();
-  let sprite_sheet_store = world.read_resource::<AssetStorage<SpriteSheet>>();
-  loader.load(
-    path,
-    SpriteSheetFormat,
-    th,
-    (),
-    &sprite_sheet_store
-  )
+  if let Some(handle) = world.res.entry::<AssetRegistry>().or_insert_with(AssetRegistry::default).loaded_sprite_sheet(&path) {
+    return handle;
+  }
+  let mut progress = ProgressCounter::new();
+  let handle = {
+    let loader = world.read_resource::<Loader>();
+    let sprite_sheet_store = world.read_resource::<AssetStorage<SpriteSheet>>();
+    loader.load(
+      path.clone(),
+      SpriteSheetFormat,
+      th,
+      &mut progress,
+      &sprite_sheet_store
+    )
+  };
+  world.res.entry::<AssetRegistry>().or_insert_with(AssetRegistry::default)
+    .sprite_sheets.push(Tracked {
+      path,
+      handle: handle.clone(),
+      progress,
+      reported: false
+    });
+  handle
+}
+
+// swaps handles of failed assets with magenta placeholders wherever they are used
+pub struct AssetFallbackSystem;
+
+impl<'a> System<'a> for AssetFallbackSystem {
+  type SystemData = (
+    Write<'a, AssetRegistry>,
+    ReadExpect<'a, Loader>,
+    Read<'a, AssetStorage<Texture>>,
+    Write<'a, AssetStorage<SpriteSheet>>,
+    WriteStorage<'a, TextureHandle>,
+    WriteStorage<'a, TextureInfo>,
+    WriteStorage<'a, SpriteRender>,
+    Option<Write<'a, SpriteCollection>>,
+  );
+
+  fn run(&mut self, (
+    mut registry,
+    loader,
+    textures,
+    mut sheets,
+    mut texture_handles,
+    mut texture_infos,
+    mut sprite_renders,
+    sprite_collection
+  ): Self::SystemData) {
+    if !registry.collect_failures() {
+      return;
+    }
+    let texture = registry.placeholder_texture(&loader, &textures);
+    let sheet = registry.placeholder_sprite_sheet(&loader, &textures, &sheets);
+    let failed_texture = |h: &TextureHandle| registry.failed_textures.contains_key(&h.id());
+    let failed_sheet = |h: &SpriteSheetHandle| registry.failed_sprite_sheets.contains_key(&h.id());
+
+    for handle in (&mut texture_handles).join().filter(|h| failed_texture(h)) {
+      *handle = texture.clone();
+    }
+    for info in (&mut texture_infos).join().filter(|i| failed_texture(&i.texture)) {
+      info.texture = texture.clone();
+    }
+    for render in (&mut sprite_renders).join().filter(|r| failed_sheet(&r.sprite_sheet)) {
+      render.sprite_sheet = sheet.clone();
+      render.sprite_number = 0;
+    }
+    if let Some(mut collection) = sprite_collection {
+      for render in collection.sprites.values_mut().filter(|r| failed_sheet(&r.sprite_sheet)) {
+        render.sprite_sheet = sheet.clone();
+        render.sprite_number = 0;
+      }
+    }
+    // sheet itself is fine, but its image is missing
+    for (id, _) in registry.failed_textures.iter() {
+      for t in registry.sprite_sheets.iter() {
+        if let Some(s) = sheets.get_mut(&t.handle) {
+          if s.texture.id() == *id {
+            s.texture = texture.clone();
+          }
+        }
+      }
+    }
+  }
 }
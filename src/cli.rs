@@ -0,0 +1,44 @@
+use std::env;
+
+pub struct Options {
+  pub seed: Option<u64>,
+  pub record: Option<String>,
+  pub replay: Option<String>,
+  pub headless: bool,
+  pub ticks: u64,
+  pub scenario: Option<String>,
+}
+
+const DEFAULT_HEADLESS_TICKS: u64 = 30 * 60 * 10;
+
+impl Default for Options {
+  fn default() -> Self {
+    Options {
+      seed: None,
+      record: None,
+      replay: None,
+      headless: false,
+      ticks: DEFAULT_HEADLESS_TICKS,
+      scenario: None,
+    }
+  }
+}
+
+impl Options {
+  pub fn from_args() -> Self {
+    let mut options = Options::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+      match arg.as_str() {
+        "--seed" => options.seed = args.next().and_then(|s| s.parse().ok()),
+        "--record" => options.record = args.next(),
+        "--replay" => options.replay = args.next(),
+        "--scenario" => options.scenario = args.next(),
+        "--headless" => options.headless = true,
+        "--ticks" => options.ticks = args.next().and_then(|s| s.parse().ok()).unwrap_or(options.ticks),
+        _ => println!("unknown argument {}", arg)
+      }
+    }
+    options
+  }
+}
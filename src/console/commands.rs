@@ -0,0 +1,282 @@
+use amethyst::{
+  core::transform::Transform,
+  ecs::{ Join, World },
+  renderer::Camera,
+};
+use serde::de::DeserializeOwned;
+use crate::game_messages::{ GameMessage, GameMessageResource };
+use crate::game_state::TileMapTilesKind;
+use crate::pawn::{ Spece, PawnId };
+use crate::rendering::tile_map::{ Tile, tile_center };
+use crate::resource::{ Resource, ResourceStack };
+use crate::simulation::{ Clock, Fire, GameSpeed, GrowthStage, Need, PlantKind, SimTime, Visibility, Weather, WeatherState };
+
+pub type CommandResult = Result<String, String>;
+
+pub struct Command {
+  pub name: &'static str,
+  pub usage: &'static str,
+  // values offered by autocomplete for the arguments
+  pub completions: &'static [&'static str],
+  pub run: fn(&mut World, &[String]) -> CommandResult,
+}
+
+pub struct CommandRegistry {
+  pub commands: Vec<Command>,
+}
+
+impl Default for CommandRegistry {
+  fn default() -> Self {
+    CommandRegistry {
+      commands: vec![
+        Command {
+          name: "help",
+          usage: "help [command]",
+          completions: &[],
+          run: help,
+        },
+        Command {
+          name: "spawn_pawn",
+          usage: "spawn_pawn <spece> <x> <y>",
+          completions: &[
+            "Human(Male,Euro,Athletic)",
+            "Human(Female,Euro,Athletic)",
+            "Human(Male,Afro,Obese)",
+            "Human(Female,Asian,Skinny)",
+            "Wolf(Male)",
+            "Wolf(Female)",
+          ],
+          run: spawn_pawn,
+        },
+        Command {
+          name: "spawn_resource",
+          usage: "spawn_resource <resource> <count> <x> <y>",
+          completions: &["Apple", "PieceOfMeat"],
+          run: spawn_resource,
+        },
+        Command {
+          name: "spawn_fire",
+          usage: "spawn_fire <fire> <x> <y>",
+          completions: &["Campfire", "Torch"],
+          run: spawn_fire,
+        },
+        Command {
+          name: "spawn_plant",
+          usage: "spawn_plant <plant> <x> <y> [stage]",
+          completions: &["BerryBush", "AppleTree", "Sprout", "Young", "Mature"],
+          run: spawn_plant,
+        },
+        Command {
+          name: "camera",
+          usage: "camera <x> <y>",
+          completions: &[],
+          run: teleport_camera,
+        },
+        Command {
+          name: "set_need",
+          usage: "set_need <pawn id> <need> <value 0..1>",
+          completions: &["Hunger", "Rest", "Warmth"],
+          run: set_need,
+        },
+        Command {
+          name: "attack",
+          usage: "attack <pawn id> <target id>",
+          completions: &[],
+          run: attack,
+        },
+        Command {
+          name: "speed",
+          usage: "speed <speed>",
+          completions: &["Paused", "Normal", "Fast", "Fastest"],
+          run: change_speed,
+        },
+        Command {
+          name: "stats",
+          usage: "stats",
+          completions: &[],
+          run: stats,
+        },
+        Command {
+          name: "weather",
+          usage: "weather [weather]",
+          completions: &["Clear", "Rain", "Snow", "Storm"],
+          run: change_weather,
+        },
+        Command {
+          name: "reveal",
+          usage: "reveal",
+          completions: &[],
+          run: reveal,
+        },
+      ]
+    }
+  }
+}
+
+impl CommandRegistry {
+  pub fn get(&self, name: &str) -> Option<&Command> {
+    self.commands.iter().find(|c| c.name == name)
+  }
+}
+
+fn arg<'a>(args: &'a [String], index: usize, usage: &str) -> Result<&'a str, String> {
+  args.get(index).map(|a| a.as_str()).ok_or_else(|| format!("usage: {}", usage))
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+  value.parse::<T>().map_err(|_| format!("{} is not a number", value))
+}
+
+// enums are written the same way as in ron files, e.g. Human(Male,Euro,Athletic)
+fn parse_ron<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+  ron::de::from_str::<T>(value).map_err(|e| format!("cannot parse {}: {}", value, e))
+}
+
+fn parse_tile(args: &[String], from: usize, usage: &str) -> Result<Tile, String> {
+  let x = parse_number::<i32>(arg(args, from, usage)?)?;
+  let y = parse_number::<i32>(arg(args, from + 1, usage)?)?;
+  Ok(Tile::new(x, y))
+}
+
+fn push_message(world: &mut World, message: GameMessage) {
+  world.res.entry::<GameMessageResource>()
+    .or_insert_with(GameMessageResource::default)
+    .messages.push(message);
+}
+
+fn help(world: &mut World, args: &[String]) -> CommandResult {
+  let registry = world.read_resource::<CommandRegistry>();
+  match args.get(0) {
+    Some(name) => registry.get(name)
+      .map(|c| c.usage.to_string())
+      .ok_or_else(|| format!("unknown command {}", name)),
+    None => Ok(registry.commands.iter().map(|c| c.usage).collect::<Vec<_>>().join("\n")),
+  }
+}
+
+fn spawn_pawn(world: &mut World, args: &[String]) -> CommandResult {
+  let usage = "spawn_pawn <spece> <x> <y>";
+  let spece = parse_ron::<Spece>(arg(args, 0, usage)?)?;
+  let tile = parse_tile(args, 1, usage)?;
+  let center = tile_center(world, &tile).ok_or_else(|| "there is no tile map".to_string())?;
+  push_message(world, GameMessage::PlacePawn(spece, tile, center));
+  Ok(format!("spawned {:?}", spece))
+}
+
+fn spawn_resource(world: &mut World, args: &[String]) -> CommandResult {
+  let usage = "spawn_resource <resource> <count> <x> <y>";
+  let resource = parse_ron::<Resource>(arg(args, 0, usage)?)?;
+  let count = parse_number::<u32>(arg(args, 1, usage)?)?;
+  let tile = parse_tile(args, 2, usage)?;
+  push_message(world, GameMessage::PlaceResource(resource, count, tile));
+  Ok(format!("spawned {} of {:?}", count, resource))
+}
+
+fn spawn_fire(world: &mut World, args: &[String]) -> CommandResult {
+  let usage = "spawn_fire <fire> <x> <y>";
+  let fire = parse_ron::<Fire>(arg(args, 0, usage)?)?;
+  let tile = parse_tile(args, 1, usage)?;
+  push_message(world, GameMessage::PlaceFire(fire, tile));
+  Ok(format!("lit {:?}", fire))
+}
+
+fn spawn_plant(world: &mut World, args: &[String]) -> CommandResult {
+  let usage = "spawn_plant <plant> <x> <y> [stage]";
+  let plant = parse_ron::<PlantKind>(arg(args, 0, usage)?)?;
+  let tile = parse_tile(args, 1, usage)?;
+  let stage = match args.get(3) {
+    None => GrowthStage::Mature,
+    Some(stage) => parse_ron::<GrowthStage>(stage)?,
+  };
+  push_message(world, GameMessage::PlacePlant(plant, stage, tile));
+  Ok(format!("planted {:?}, {:?}", plant, stage))
+}
+
+fn teleport_camera(world: &mut World, args: &[String]) -> CommandResult {
+  let tile = parse_tile(args, 0, "camera <x> <y>")?;
+  let center = tile_center(world, &tile).ok_or_else(|| "there is no tile map".to_string())?;
+  let cameras = world.read_storage::<Camera>();
+  let mut transforms = world.write_storage::<Transform>();
+  let mut moved = 0;
+  for (_, transform) in (&cameras, &mut transforms).join() {
+    let z = transform.translation().z;
+    transform.set_xyz(center.x, center.y, z);
+    moved += 1;
+  }
+  if moved == 0 {
+    Err("there is no camera".to_string())
+  } else {
+    Ok(format!("camera moved to {:.1}, {:.1}", center.x, center.y))
+  }
+}
+
+fn set_need(world: &mut World, args: &[String]) -> CommandResult {
+  let usage = "set_need <pawn id> <need> <value 0..1>";
+  let id = PawnId(parse_number::<u32>(arg(args, 0, usage)?)?);
+  let need = parse_ron::<Need>(arg(args, 1, usage)?)?;
+  let value = parse_number::<f32>(arg(args, 2, usage)?)?;
+  push_message(world, GameMessage::SetNeed(id, need, value));
+  Ok(format!("{:?} of #{} set to {:.2}", need, id.0, value))
+}
+
+fn attack(world: &mut World, args: &[String]) -> CommandResult {
+  let usage = "attack <pawn id> <target id>";
+  let id = PawnId(parse_number::<u32>(arg(args, 0, usage)?)?);
+  let target = PawnId(parse_number::<u32>(arg(args, 1, usage)?)?);
+  if id == target {
+    return Err("pawn cannot attack itself".to_string());
+  }
+  push_message(world, GameMessage::Attack(id, target));
+  Ok(format!("#{} attacks #{}", id.0, target.0))
+}
+
+fn change_speed(world: &mut World, args: &[String]) -> CommandResult {
+  let speed = match arg(args, 0, "speed <speed>")?.to_lowercase().as_str() {
+    "paused" | "0" => GameSpeed::Paused,
+    "normal" | "1" => GameSpeed::Normal,
+    "fast" | "2" => GameSpeed::Fast,
+    "fastest" | "3" => GameSpeed::Fastest,
+    other => return Err(format!("unknown speed {}", other)),
+  };
+  *world.write_resource::<GameSpeed>() = speed;
+  Ok(format!("speed is {:?}", speed))
+}
+
+fn stats(world: &mut World, _args: &[String]) -> CommandResult {
+  world.register::<Spece>();
+  world.register::<TileMapTilesKind>();
+  world.register::<ResourceStack>();
+  let mut lines = Vec::new();
+  lines.push(format!("entities: {}", world.entities().join().count()));
+  lines.push(format!("pawns: {}", world.read_storage::<Spece>().join().count()));
+  lines.push(format!("tiles: {}", world.read_storage::<TileMapTilesKind>().join().count()));
+  lines.push(format!("resource stacks: {}", world.read_storage::<ResourceStack>().join().count()));
+  if let Some(time) = world.res.try_fetch::<SimTime>() {
+    lines.push(format!("tick: {}", time.tick));
+  }
+  if let Some(clock) = world.res.try_fetch::<Clock>() {
+    lines.push(clock.describe());
+  }
+  Ok(lines.join("\n"))
+}
+
+fn change_weather(world: &mut World, args: &[String]) -> CommandResult {
+  match args.get(0) {
+    None => {
+      let weather = world.res.entry::<WeatherState>().or_insert_with(WeatherState::default).clone();
+      Ok(format!("{:?} for {} more minutes", weather.current, weather.minutes_left))
+    }
+    Some(value) => {
+      let weather = parse_ron::<Weather>(value)?;
+      push_message(world, GameMessage::SetWeather(weather));
+      Ok(format!("weather set to {:?}", weather))
+    }
+  }
+}
+
+// toggles fog of war, only for the player's eyes, simulation does not care
+fn reveal(world: &mut World, _args: &[String]) -> CommandResult {
+  let mut visibility = world.res.entry::<Visibility>().or_insert_with(Visibility::default);
+  visibility.revealed = !visibility.revealed;
+  Ok(format!("fog of war {}", if visibility.revealed { "off" } else { "on" }))
+}
@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+use amethyst::{
+  ecs::{ Component, Join, NullStorage, Read, ReadStorage, System, Write, WriteStorage },
+  prelude::World,
+};
+use winit::VirtualKeyCode;
+use crate::input::InputState;
+use crate::states::PauseMenu;
+use crate::ui::text_panel::{ Anchor, TextPanel, create_text_panel };
+
+pub mod commands;
+pub use self::commands::{ Command, CommandRegistry, CommandResult };
+
+const TOGGLE_KEY: VirtualKeyCode = VirtualKeyCode::Grave;
+const OUTPUT_LINES: usize = 14;
+const HISTORY_SIZE: usize = 50;
+
+#[derive(Default)]
+pub struct ConsolePanel;
+
+impl Component for ConsolePanel {
+  type Storage = NullStorage<Self>;
+}
+
+#[derive(Default)]
+pub struct Console {
+  pub open: bool,
+  pub line: String,
+  pub output: VecDeque<String>,
+  pub history: Vec<String>,
+  // position while browsing history with up and down keys
+  history_index: Option<usize>,
+  // entered lines, executed by the game state which owns the world
+  pending: Vec<String>,
+}
+
+impl Console {
+  pub fn print(&mut self, text: &str) {
+    for line in text.lines() {
+      self.output.push_back(line.to_string());
+    }
+    while self.output.len() > OUTPUT_LINES {
+      self.output.pop_front();
+    }
+  }
+
+  fn submit(&mut self) {
+    let line = self.line.trim().to_string();
+    self.line.clear();
+    self.history_index = None;
+    if line.is_empty() {
+      return;
+    }
+    self.print(&format!("> {}", line));
+    if self.history.last() != Some(&line) {
+      self.history.push(line.clone());
+    }
+    if self.history.len() > HISTORY_SIZE {
+      self.history.remove(0);
+    }
+    self.pending.push(line);
+  }
+
+  fn browse_history(&mut self, older: bool) {
+    if self.history.is_empty() {
+      return;
+    }
+    let last = self.history.len() - 1;
+    self.history_index = match (self.history_index, older) {
+      (None, true) => Some(last),
+      (None, false) => None,
+      (Some(i), true) => Some(i.saturating_sub(1)),
+      (Some(i), false) if i < last => Some(i + 1),
+      (Some(_), false) => None,
+    };
+    self.line = match self.history_index {
+      None => String::new(),
+      Some(i) => self.history[i].clone(),
+    };
+  }
+
+  fn complete(&mut self, registry: &CommandRegistry) {
+    let mut words = tokenize(&self.line);
+    let completing_new_word = self.line.is_empty() || self.line.ends_with(' ');
+    let prefix = if completing_new_word { String::new() } else { words.pop().unwrap_or_default() };
+    let candidates: Vec<&str> = if words.is_empty() {
+      registry.commands.iter().map(|c| c.name).filter(|n| n.starts_with(&prefix)).collect()
+    } else {
+      match registry.get(&words[0]) {
+        None => Vec::new(),
+        Some(command) => command.completions.iter()
+          .cloned()
+          .filter(|c| c.to_lowercase().starts_with(&prefix.to_lowercase()))
+          .collect()
+      }
+    };
+    let completed = match candidates.len() {
+      0 => return,
+      1 => format!("{} ", candidates[0]),
+      _ => {
+        self.print(&candidates.join("  "));
+        common_prefix(&candidates)
+      }
+    };
+    if completed.len() < prefix.len() {
+      return;
+    }
+    words.push(completed);
+    self.line = words.join(" ");
+  }
+}
+
+fn common_prefix(words: &[&str]) -> String {
+  let first = words[0];
+  let mut length = first.len();
+  for word in words.iter().skip(1) {
+    length = first.chars().zip(word.chars())
+      .take_while(|(a, b)| a == b)
+      .count()
+      .min(length);
+  }
+  first.chars().take(length).collect()
+}
+
+// splits by spaces, but keeps brackets together, so Human(Male, Euro, Athletic) is one word
+pub fn tokenize(line: &str) -> Vec<String> {
+  let mut words = Vec::new();
+  let mut word = String::new();
+  let mut depth = 0;
+  for c in line.chars() {
+    match c {
+      '(' => depth += 1,
+      ')' => depth -= 1,
+      _ => {}
+    }
+    if c.is_whitespace() && depth <= 0 {
+      if !word.is_empty() {
+        words.push(word.clone());
+        word.clear();
+      }
+    } else if !c.is_whitespace() {
+      word.push(c);
+    }
+  }
+  if !word.is_empty() {
+    words.push(word);
+  }
+  words
+}
+
+pub fn is_console_open(world: &World) -> bool {
+  world.res.try_fetch::<Console>().map(|c| c.open).unwrap_or(false)
+}
+
+pub fn create_console(world: &mut World) {
+  world.register::<ConsolePanel>();
+  world.res.entry::<CommandRegistry>().or_insert_with(CommandRegistry::default);
+  let panel = create_text_panel(world, Anchor::TopLeft, (310.0, -160.0), (600.0, 300.0));
+  if let Err(e) = world.write_storage::<ConsolePanel>().insert(panel, ConsolePanel) {
+    println!("cannot create console: {}", e);
+  }
+}
+
+pub fn run_console_commands(world: &mut World) {
+  let lines: Vec<String> = match world.res.try_fetch_mut::<Console>() {
+    None => return,
+    Some(mut console) => console.pending.drain(..).collect()
+  };
+  for line in lines {
+    let words = tokenize(&line);
+    let run = world.res.try_fetch::<CommandRegistry>()
+      .and_then(|registry| registry.get(&words[0]).map(|c| c.run));
+    let result = match run {
+      None => Err(format!("unknown command {}, try help", words[0])),
+      Some(run) => run(world, &words[1..]),
+    };
+    let mut console = world.write_resource::<Console>();
+    match result {
+      Ok(text) => console.print(&text),
+      Err(text) => console.print(&format!("error: {}", text)),
+    }
+  }
+}
+
+pub struct ConsoleSystem;
+
+impl<'a> System<'a> for ConsoleSystem {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PauseMenu>,
+    Write<'a, Console>,
+    Read<'a, CommandRegistry>,
+    ReadStorage<'a, ConsolePanel>,
+    WriteStorage<'a, TextPanel>,
+  );
+
+  fn run(&mut self, (input, pause_menu, mut console, registry, consoles, mut panels): Self::SystemData) {
+    if pause_menu.open {
+      // keys belong to the pause menu, console stays as it was
+    } else if input.is_key_down(TOGGLE_KEY) {
+      console.open = !console.open;
+    } else if console.open {
+      if input.is_key_down(VirtualKeyCode::Escape) {
+        console.open = false;
+      }
+      // toggle key also arrives as a character
+      console.line.extend(input.typed.chars().filter(|c| *c != '`' && *c != '~'));
+      if input.is_key_down(VirtualKeyCode::Back) {
+        console.line.pop();
+      }
+      if input.is_key_down(VirtualKeyCode::Tab) {
+        console.complete(&registry);
+      }
+      if input.is_key_down(VirtualKeyCode::Up) {
+        console.browse_history(true);
+      }
+      if input.is_key_down(VirtualKeyCode::Down) {
+        console.browse_history(false);
+      }
+      if input.is_key_down(VirtualKeyCode::Return) {
+        console.submit();
+      }
+    }
+
+    let text = if console.open {
+      let mut lines: Vec<String> = console.output.iter().cloned().collect();
+      lines.push(format!("> {}_", console.line));
+      lines.join("\n")
+    } else {
+      String::new()
+    };
+    for (_, panel) in (&consoles, &mut panels).join() {
+      if panel.text != text {
+        panel.text = text.clone();
+      }
+    }
+  }
+}
@@ -1,16 +1,20 @@
 use amethyst::{
-  ecs::{ Component, DenseVecStorage },
+  ecs::{ Component, DenseVecStorage, Join },
   prelude::{ Builder, World},
   core::{
     nalgebra::{ Matrix3 }
   }
 };
 use crate::asset_loader::load_png_texture;
+use crate::input::{ InputConfig, CursorMode };
 use crate::rendering::screen_space::screen_rect::{ 
   ScreenRect,
   Transform2D
 };
 pub mod mouse_pointer_system;
+pub mod tile_picking_system;
+
+pub const CURSOR_LAYER: u8 = 255;
 
 pub struct Cursor;
 impl Component for Cursor{ 
@@ -21,12 +25,21 @@ pub fn create_cursor(world: &mut World) {
   world.register::<ScreenRect>();
   world.register::<Transform2D>();
   world.register::<Cursor>();
+  // in absolute mode the OS cursor is shown instead of the sprite
+  let mode = world.res.try_fetch::<InputConfig>().map(|config| config.cursor_mode);
+  if mode == Some(CursorMode::Absolute) {
+    return;
+  }
+  if (&world.read_storage::<Cursor>()).join().next().is_some() {
+    return;
+  }
   let handle = load_png_texture(world, "./resources/sprites/ui/cursor.png".to_string());
   let mut rect: ScreenRect = Default::default();
   rect.size.x = 48.0;
   rect.size.y = 80.0;
   rect.size /= 4.0;
   rect.position.y = -rect.size.y;
+  rect.layer = CURSOR_LAYER;
   let transform = Transform2D {
     model: Matrix3::<f32>::identity() 
   };
@@ -0,0 +1,111 @@
+use amethyst::{
+  core::{
+    nalgebra::{ Vector2, Vector3 },
+    specs::{
+      prelude::{
+        Entities, Entity, Join, ReadStorage, Read, Write, System,
+      }
+    },
+    transform::{ GlobalTransform }
+  },
+  renderer::{
+    ActiveCamera, Camera, ScreenDimensions
+  }
+};
+use nalgebra_glm::*;
+use crate::game_state::TileMapTilesKind;
+use crate::input::InputState;
+use crate::rendering::camera_getter::get_camera;
+use std::collections::HashMap;
+use crate::rendering::tile_map::{ Tile, TileMap, Elevation };
+
+#[derive(Default)]
+pub struct PickedTile {
+  // cursor position in world coordinates
+  pub world_position: Option<Vector2<f32>>,
+  // cube coordinate under cursor, even if map has no tile there
+  pub tile: Option<Tile>,
+  // world position of the tile center
+  pub tile_center: Option<Vector2<f32>>,
+  pub entity: Option<Entity>,
+}
+
+pub struct TilePickingSystem;
+
+impl<'a> System<'a> for TilePickingSystem {
+  type SystemData = (
+    Read<'a, InputState>,
+    Option<Read<'a, ActiveCamera>>,
+    ReadStorage<'a, Camera>,
+    ReadStorage<'a, GlobalTransform>,
+    Option<Read<'a, ScreenDimensions>>,
+    ReadStorage<'a, TileMap>,
+    Entities<'a>,
+    ReadStorage<'a, Tile>,
+    ReadStorage<'a, TileMapTilesKind>,
+    ReadStorage<'a, Elevation>,
+    Write<'a, PickedTile>,
+  );
+
+  fn run(&mut self, (
+    input,
+    active_camera,
+    camera,
+    camera_transform,
+    screen_dimensions,
+    tile_map,
+    entities,
+    tiles,
+    kinds,
+    elevations,
+    mut picked
+  ): Self::SystemData) {
+    *picked = PickedTile::default();
+    let maybe_camera = get_camera(
+      active_camera,
+      &camera,
+      &camera_transform
+    );
+    let dimensions = match screen_dimensions {
+      None => return,
+      Some(dim) => vec2(dim.width() as f32, dim.height() as f32)
+    };
+
+    let screen_matrix = match maybe_camera {
+      None => return,
+      Some((cam, glob)) => {
+
+        let view = glob
+          .0
+          .try_inverse()
+          .expect("it must be possible to inverse so simple matrix");
+        (view * cam.proj).try_inverse().expect("once again")
+      }
+
+    };
+    let mut mouse_pointer_position = input.cursor;
+    mouse_pointer_position.x /= dimensions.x;
+    mouse_pointer_position.y /= dimensions.y;
+    mouse_pointer_position *= 2.0;
+    mouse_pointer_position -= vec2(1.0, 1.0);
+    let mp = screen_matrix * vec4(mouse_pointer_position.x, mouse_pointer_position.y, 1.0, 1.0);
+    picked.world_position = Some(mp.xy());
+    let heights: HashMap<Vector3<i32>, i32> = (&tiles, &kinds, (&elevations).maybe()).join()
+      .map(|(t, _, e)| (t.position, e.map(|e| e.0).unwrap_or(0)))
+      .collect();
+    let levels = (
+      heights.values().cloned().min().unwrap_or(0),
+      heights.values().cloned().max().unwrap_or(0)
+    );
+    let elevation_of = |t: &Tile| heights.get(&t.position).cloned();
+    for tm in (&tile_map).join() {
+      if let Some(tile) = tm.calculate_raised_tile(&mp.xy(), levels, &elevation_of) {
+        picked.entity = (&entities, &tiles, &kinds).join()
+          .find(|(_, t, _)| t.position == tile.position)
+          .map(|(e, _, _)| e);
+        picked.tile_center = Some(tm.tile_center(&tile, elevation_of(&tile).unwrap_or(0)));
+        picked.tile = Some(tile);
+      }
+    }
+  }
+}
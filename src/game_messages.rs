@@ -1,8 +1,27 @@
 use crate::rendering::tile_map::Tile;
-use crate::pawn::Spece;
+use crate::pawn::{ Spece, PawnId };
+use crate::resource::Resource;
+use crate::simulation::{ Need, Fire, Weather, PlantKind, GrowthStage };
+use crate::game_state::TileMapTilesKind;
 use amethyst::core::nalgebra::{ Vector2 };
+use serde_derive::{ Serialize, Deserialize };
+
+#[derive(Clone, Serialize, Deserialize)]
 pub enum GameMessage {
   PlacePawn(Spece, Tile, Vector2<f32>),
+  SetDrafted(PawnId, bool),
+  SetPrioritized(PawnId, bool),
+  // appended to the job queue of the pawn
+  QueueMoveTo(PawnId, Tile),
+  PlaceResource(Resource, u32, Tile),
+  SetNeed(PawnId, Need, f32),
+  PaintTerrain(Tile, TileMapTilesKind),
+  Erase(Tile),
+  PlaceFire(Fire, Tile),
+  SetWeather(Weather),
+  PlacePlant(PlantKind, GrowthStage, Tile),
+  // attacker drops what it does and goes after the target
+  Attack(PawnId, PawnId),
 }
 
 pub struct GameMessageResource {
@@ -23,19 +23,34 @@ use amethyst::{
 
 
 use crate::pawn::sprites::SpriteCollection;
-use crate::pawn::place_debug_pawn;
-use crate::cursor::create_cursor;
+use crate::pawn::{ place_debug_pawn, find_pawn };
+use crate::ui::speed_widget::create_speed_widget;
+use crate::ui::tile_inspector::create_tile_inspector;
+use crate::ui::pawn_inspector::create_pawn_inspector;
+use crate::ui::tool_strip::create_tool_strip;
+use crate::ui::clock_widget::create_clock_widget;
+use crate::rendering::weather_overlay::create_weather_overlay;
+use crate::console::{ create_console, run_console_commands, is_console_open };
 // use crate::texture_loader::load_png_texture;
-use crate::rendering::tile_map::create_debug_tile_map;
+use crate::scenario::{ Scenario, SelectedScenario, apply_scenario };
 use crate::game_messages::{ GameMessage, GameMessageResource };
+use crate::states::{ PauseState, grab_cursor, free_cursor };
+use crate::replay::Replay;
+use crate::simulation::{ Simulation, SimTime, Job, Jobs, Needs, WeatherState, place_fire, place_plant, load_fire_sprites, load_plant_sprites, CATCH_UP_TICKS_PER_FRAME };
+use crate::resource::place_resource;
+use crate::tools::{ paint_terrain, erase_at };
 
 use amethyst::prelude::*;
+use amethyst::ecs::{ Component, DenseVecStorage, FlaggedStorage };
+use serde_derive::{ Serialize, Deserialize };
 
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
 pub enum TileMapTilesKind {
   Grass,
   Water,
-  Dirt
+  Dirt,
+  // frozen water, only the simulation makes it
+  Ice
 }
 
 unsafe impl Send for TileMapTilesKind {}
@@ -46,8 +61,14 @@ impl Default for TileMapTilesKind {
   }
 }
 
+// flagged, so autotiling learns which tiles changed
+impl Component for TileMapTilesKind {
+  type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+}
+
 pub struct State {
   window_resolution: Vector2<u16>,
+  simulation: Option<Simulation>,
   // pawns: Vec<Pawn>
 }
 
@@ -57,87 +78,64 @@ impl State {
     // let pawns = vec!(Pawn::new());
     State {
       // pawns,
+      simulation: None,
       window_resolution: Vector2::<u16>::new(width, height)
     }
   }
 
-  fn init_map(&mut self, world: &mut World) {
-    create_debug_tile_map(world, 4, "./resources/sprites/terrain/tiles.png".to_string());
-  }
-
-  fn initialize_pawns(&mut self, world: &mut World) {
-    SpriteCollection::create_resource(world);
-    // world.register::<Pawn>();
-
-    /*
-    for pawn in &self.pawns {
-      
-      let pawn_sprite = SpriteRender {
-        sprite_sheet: ssh.clone(),
-        sprite_number: 0
-      };
-
-      let mut tr = Transform::default();
-      tr.set_xyz(250.5, 250.5, 0.0);
-      tr.set_scale(0.2, 0.2, 0.4);
-      world.create_entity()
-        .with(pawn_sprite)
-        .with(pawn.clone())
-        .with(tr)
-        .build();
-    }
-    */
-  }
-
 }
 
 impl SimpleState for State{
   fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+    run_console_commands(data.world);
     let mut msgs = Vec::<GameMessage>::new();
     {
       let mut messages = data.world.write_resource::<GameMessageResource>();
       msgs.append(&mut messages.messages);
     }
-
-      
-    for msg in msgs {
-      match msg {
-        GameMessage::PlacePawn(spece, tile, initial) => place_debug_pawn(data.world, spece, initial, tile),
-        _ => continue
+    // a loaded session is restored over several frames, so the window keeps responding
+    let tick = data.world.read_resource::<SimTime>().tick;
+    let catch_up = data.world.read_resource::<Replay>().catch_up(tick);
+    let ticks = match (&mut self.simulation, catch_up) {
+      (None, _) => 0,
+      (Some(_), Some(ticks)) => ticks.min(CATCH_UP_TICKS_PER_FRAME),
+      (Some(simulation), None) => simulation.due_ticks(data.world) as u64
+    };
+    handle_messages(data.world, msgs);
+    for _ in 0..ticks {
+      if let Some(simulation) = &mut self.simulation {
+        simulation.tick(data.world);
       }
+      handle_messages(data.world, Vec::new());
     }
     Trans::None
   }
   fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
     let world = data.world;
-    self.initialize_pawns(world);
-
-
-    // let sprite_handle = load_sprite_sheet(world);
-
-    // self.initialize_pawns(world, sprite_handle);
-    self.init_map(world);
-    create_cursor(world);
+    self.simulation = Some(Simulation::new(world));
 
     initialize_camera(world, &self.window_resolution);
-    use amethyst::renderer::mouse;
-    let mut msg = world.res.fetch_mut::<WindowMessages>();
+    grab_cursor(world);
+  }
 
-    mouse::grab_cursor(&mut msg);
-    mouse::hide_cursor(&mut msg);
+  fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+    grab_cursor(data.world);
+  }
+
+  fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+    let tick = data.world.read_resource::<SimTime>().tick;
+    data.world.write_resource::<Replay>().save(tick);
+    free_cursor(data.world);
   }
 
   fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
     match &event {
       StateEvent::Window(event) => {
         if is_close_requested(&event) {
-          println!("close requested, but we shall not quit right now");
-          Trans::None
-        } else if is_key_down(&event, VirtualKeyCode::Escape) {
-          use amethyst::renderer::mouse;
-          let mut msg = data.world.res.fetch_mut::<WindowMessages>();
-          mouse::release_cursor(&mut msg);
+          free_cursor(data.world);
           Trans::Quit
+        } else if is_key_down(&event, VirtualKeyCode::Escape) && !is_console_open(data.world) {
+          Trans::Push(Box::new(PauseState::new()))
         } else { Trans::None }
       }
       _ => Trans::None
@@ -163,7 +161,97 @@ fn load_sprite_sheet(world: &mut World) -> SpriteSheetHandle {
 }
 */
 
-fn initialize_camera(world: &mut World, resolution: &Vector2<u16>) {
+// messages are bound to simulation tick, so replay applies them at the very same moment
+pub fn handle_messages(world: &mut World, live: Vec<GameMessage>) {
+  let tick = world.read_resource::<SimTime>().tick;
+  let msgs = world.write_resource::<Replay>().process(tick, live);
+  for msg in msgs {
+    match msg {
+      GameMessage::PlacePawn(spece, tile, initial) => place_debug_pawn(world, spece, initial, tile),
+      GameMessage::SetDrafted(id, drafted) => {
+        let mut jobs = world.write_storage::<Jobs>();
+        if let Some(j) = find_pawn(world, id).and_then(|e| jobs.get_mut(e)) {
+          j.drafted = drafted;
+        }
+      }
+      GameMessage::SetPrioritized(id, prioritized) => {
+        let mut jobs = world.write_storage::<Jobs>();
+        if let Some(j) = find_pawn(world, id).and_then(|e| jobs.get_mut(e)) {
+          j.prioritized = prioritized;
+        }
+      }
+      GameMessage::QueueMoveTo(id, tile) => {
+        let mut jobs = world.write_storage::<Jobs>();
+        if let Some(j) = find_pawn(world, id).and_then(|e| jobs.get_mut(e)) {
+          j.queue.push_back(Job::MoveTo(tile));
+        }
+      }
+      GameMessage::PlaceResource(resource, count, tile) => place_resource(world, resource, count, tile),
+      GameMessage::SetNeed(id, need, value) => {
+        let mut needs = world.write_storage::<Needs>();
+        if let Some(n) = find_pawn(world, id).and_then(|e| needs.get_mut(e)) {
+          n.set(need, value);
+        }
+      }
+      GameMessage::PaintTerrain(tile, kind) => paint_terrain(world, &tile, kind),
+      GameMessage::Erase(tile) => erase_at(world, &tile),
+      GameMessage::PlaceFire(fire, tile) => place_fire(world, fire, tile),
+      GameMessage::PlacePlant(kind, stage, tile) => place_plant(world, kind, stage, tile),
+      GameMessage::Attack(id, target) => {
+        let mut jobs = world.write_storage::<Jobs>();
+        if let Some(j) = find_pawn(world, id).and_then(|e| jobs.get_mut(e)) {
+          j.current = Some(Job::Attack(target));
+        }
+      }
+      GameMessage::SetWeather(weather) => world.res.entry::<WeatherState>()
+        .or_insert_with(WeatherState::default)
+        .current = weather,
+    }
+  }
+}
+
+// everything the game needs before it starts, loading state waits for the assets requested here
+pub fn load_world(world: &mut World) {
+  initialize_pawns(world);
+  load_fire_sprites(world);
+  load_plant_sprites(world);
+  let selected = world.res.entry::<SelectedScenario>().or_insert_with(SelectedScenario::default).clone();
+  let scenario = Scenario::from_selection(&selected);
+  apply_scenario(world, &scenario, true);
+  create_speed_widget(world);
+  create_clock_widget(world);
+  create_weather_overlay(world);
+  create_tile_inspector(world);
+  create_pawn_inspector(world);
+  create_tool_strip(world);
+  create_console(world);
+}
+
+fn initialize_pawns(world: &mut World) {
+  SpriteCollection::create_resource(world);
+  // world.register::<Pawn>();
+
+  /*
+  for pawn in &self.pawns {
+    
+    let pawn_sprite = SpriteRender {
+      sprite_sheet: ssh.clone(),
+      sprite_number: 0
+    };
+
+    let mut tr = Transform::default();
+    tr.set_xyz(250.5, 250.5, 0.0);
+    tr.set_scale(0.2, 0.2, 0.4);
+    world.create_entity()
+      .with(pawn_sprite)
+      .with(pawn.clone())
+      .with(tr)
+      .build();
+  }
+  */
+}
+
+pub fn initialize_camera(world: &mut World, resolution: &Vector2<u16>) {
   let mut transform = Transform::default();
   transform.set_xyz(0.0, 0.1, 0.4);
   let w = resolution.x as f32;
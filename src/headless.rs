@@ -0,0 +1,54 @@
+use amethyst::{
+  ecs::{ Join, World },
+};
+use crate::cli::Options;
+use crate::game_messages::GameMessageResource;
+use crate::game_state::handle_messages;
+use crate::pawn::Spece;
+use crate::random::GameSeed;
+use crate::replay::Replay;
+use crate::rendering::tile_map::Tile;
+use crate::scenario::{ Scenario, SelectedScenario, apply_scenario };
+use crate::simulation::{ Clock, Needs, Simulation, SimTime };
+
+const REPORT_EVERY: u64 = 30 * 60;
+
+// Runs simulation without window and any rendering, for soak tests and balance experiments on CI.
+pub fn run(options: &Options, seed: GameSeed, replay: Replay, scenario: SelectedScenario) -> amethyst::Result<()> {
+  let mut world = World::new();
+  world.add_resource(seed);
+  world.add_resource(replay);
+  world.add_resource(GameMessageResource::default());
+  let scenario = Scenario::from_selection(&scenario);
+  apply_scenario(&mut world, &scenario, false);
+  let mut simulation = Simulation::new(&mut world);
+
+  println!("headless simulation of {} with seed {} for {} ticks", scenario.name, seed.0, options.ticks);
+  handle_messages(&mut world, Vec::new());
+  for _ in 0..options.ticks {
+    simulation.tick(&mut world);
+    handle_messages(&mut world, Vec::new());
+    if world.read_resource::<SimTime>().tick % REPORT_EVERY == 0 {
+      report(&world);
+    }
+  }
+  let tick = world.read_resource::<SimTime>().tick;
+  world.write_resource::<Replay>().save(tick);
+  report(&world);
+  Ok(())
+}
+
+fn report(world: &World) {
+  let tick = world.read_resource::<SimTime>().tick;
+  let species = world.read_storage::<Spece>();
+  let tiles = world.read_storage::<Tile>();
+  let needs = world.read_storage::<Needs>();
+  let pawns: Vec<_> = (&species, &tiles, &needs).join().collect();
+  println!("tick {}, {}: {} pawns", tick, world.read_resource::<Clock>().describe(), pawns.len());
+  for (spece, tile, need) in pawns {
+    println!(
+      "  {:?} at {}: hunger {:.2}, rest {:.2}, warmth {:.2}",
+      spece, tile.position, need.hunger, need.rest, need.warmth
+    );
+  }
+}
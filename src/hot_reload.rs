@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use amethyst::ecs::{ System, Write };
+use crate::resource::ResourceDictionary;
+
+// how many frames pass between file checks
+const CHECK_EVERY: u32 = 30;
+
+pub struct WatchedFile {
+  path: PathBuf,
+  modified: Option<SystemTime>,
+}
+
+impl WatchedFile {
+  pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+    let path = path.into();
+    let modified = modified(&path);
+    WatchedFile {
+      path,
+      modified
+    }
+  }
+
+  pub fn path(&self) -> &PathBuf {
+    &self.path
+  }
+
+  pub fn changed(&mut self) -> bool {
+    let current = modified(&self.path);
+    if current != self.modified {
+      self.modified = current;
+      current.is_some()
+    } else {
+      false
+    }
+  }
+}
+
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+  fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// Shaders are read from disk when pipeline is compiled, falling back to the embedded copy.
+// Live shader reload is left out of hot reloading: amethyst 0.10 compiles the effect of a pass
+// once, when the render system is built, so shader edits still take a restart.
+pub fn load_shader(path: &str, embedded: &[u8]) -> Vec<u8> {
+  match fs::read(path) {
+    Ok(source) => source,
+    Err(_) => embedded.to_vec()
+  }
+}
+
+pub struct DataReloadSystem {
+  frame: u32,
+  dictionary: WatchedFile,
+}
+
+impl DataReloadSystem {
+  pub fn new() -> Self {
+    DataReloadSystem {
+      frame: 0,
+      dictionary: WatchedFile::new(crate::resource::DICTIONARY_PATH),
+    }
+  }
+}
+
+impl<'a> System<'a> for DataReloadSystem {
+  type SystemData = Write<'a, ResourceDictionary>;
+
+  fn run(&mut self, mut dictionary: Self::SystemData) {
+    self.frame += 1;
+    if self.frame % CHECK_EVERY != 0 {
+      return;
+    }
+    if self.dictionary.changed() {
+      match ResourceDictionary::load(self.dictionary.path()) {
+        Ok(d) => {
+          println!("resource dictionary is reloaded");
+          *dictionary = d;
+        }
+        Err(e) => println!("{}", e)
+      }
+    }
+  }
+}
@@ -0,0 +1,31 @@
+use serde_derive::{ Serialize, Deserialize };
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CursorMode {
+  // OS cursor is grabbed and hidden, raw mouse motion moves a virtual one
+  Virtual,
+  // OS cursor position is followed as is, suitable for windowed play and tablets
+  Absolute,
+}
+
+impl Default for CursorMode {
+  fn default() -> Self {
+    CursorMode::Virtual
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputConfig {
+  pub cursor_mode: CursorMode,
+  pub sensitivity: f32,
+}
+
+impl Default for InputConfig {
+  fn default() -> Self {
+    InputConfig {
+      cursor_mode: CursorMode::default(),
+      sensitivity: 1.0,
+    }
+  }
+}
@@ -0,0 +1,129 @@
+use winit::{
+  ElementState, WindowEvent, DeviceEvent, Event, KeyboardInput, MouseScrollDelta
+};
+
+use amethyst::{
+  renderer::{ ScreenDimensions },
+  core::{
+    shrev::{ EventChannel, ReaderId },
+    nalgebra::{ Vector2 },
+    specs::{
+      prelude::{
+        Read, Write, System, Resources
+      }
+    }
+  }
+};
+use nalgebra_glm::{ clamp_vec, vec2 };
+use super::{ InputState, InputConfig, CursorMode };
+
+pub struct InputSystem {
+  initial_cursor: Vector2<f32>,
+  dimensions: Vector2<f32>,
+  event_reader: Option<ReaderId<Event>>,
+}
+
+impl InputSystem {
+  pub fn new(cursor: Vector2<f32>) -> Self {
+    InputSystem {
+      initial_cursor: cursor,
+      dimensions: cursor * 2.0,
+      event_reader: None,
+    }
+  }
+
+  // keeps virtual cursor at the same relative place when window is resized
+  fn follow_resize(&mut self, state: &mut InputState, dimensions: Vector2<f32>) {
+    if dimensions == self.dimensions {
+      return;
+    }
+    if self.dimensions.x > 0.0 && self.dimensions.y > 0.0 {
+      let relative = state.cursor.component_div(&self.dimensions);
+      state.move_cursor(relative.component_mul(&dimensions));
+    }
+    self.dimensions = dimensions;
+  }
+}
+
+fn process_keyboard(state: &mut InputState, inp: &KeyboardInput) {
+  let key = match inp.virtual_keycode {
+    None => return,
+    Some(k) => k
+  };
+  match inp.state {
+    ElementState::Pressed => state.key_pressed(key),
+    ElementState::Released => state.key_released(key),
+  }
+}
+
+fn wheel_amount(delta: &MouseScrollDelta) -> f32 {
+  match *delta {
+    MouseScrollDelta::LineDelta(_, y) => y,
+    MouseScrollDelta::PixelDelta(pos) => pos.y as f32,
+  }
+}
+
+impl<'a> System<'a> for InputSystem {
+  type SystemData = (
+    Read<'a, EventChannel<Event>>,
+    Write<'a, InputState>,
+    Read<'a, InputConfig>,
+    Option<Read<'a, ScreenDimensions>>
+  );
+
+  fn run(&mut self, (event_channel, mut state, config, screen_dim): Self::SystemData) {
+    match self.event_reader.as_mut() {
+      None => {
+        println!("setup of InputSystem isn`t called");
+        return;
+      }
+      Some(mut reader) => {
+        let (dimensions, hidpi) = match screen_dim {
+          None => (vec2(0.0, 0.0), 1.0),
+          Some(ref sd) => (vec2(sd.width(), sd.height()), sd.hidpi_factor())
+        };
+        state.begin_frame();
+        if config.cursor_mode == CursorMode::Virtual {
+          self.follow_resize(&mut state, dimensions);
+        }
+        for event in event_channel.read(&mut reader) {
+          if let Event::DeviceEvent { ref event, .. } = *event {
+            if let DeviceEvent::MouseMotion { delta } = *event {
+              if config.cursor_mode == CursorMode::Virtual {
+                let (x, y) = delta;
+                let move_by = Vector2::<f32>::new(x as f32, -y as f32) * config.sensitivity;
+                let new_coords = clamp_vec(&(state.cursor + move_by), &vec2(0.0, 0.0), &dimensions);
+                state.move_cursor(new_coords);
+              }
+            }
+          }
+          if let Event::WindowEvent{ ref event, .. } = *event {
+            match *event {
+              WindowEvent::CursorMoved { position, .. } => {
+                if config.cursor_mode == CursorMode::Absolute {
+                  // window reports logical pixels from top left corner, screen space is physical from bottom left
+                  let (x, y): (f64, f64) = position.to_physical(hidpi).into();
+                  state.move_cursor(vec2(x as f32, dimensions.y - y as f32));
+                }
+              }
+              WindowEvent::KeyboardInput { input, .. } => process_keyboard(&mut state, &input),
+              WindowEvent::MouseInput { state: ElementState::Pressed, button, .. } => state.button_pressed(button),
+              WindowEvent::MouseInput { state: ElementState::Released, button, .. } => state.button_released(button),
+              WindowEvent::MouseWheel { ref delta, .. } => state.wheel_delta += wheel_amount(delta),
+              WindowEvent::ReceivedCharacter(c) if !c.is_control() => state.typed.push(c),
+              WindowEvent::Focused(false) => state.release_all(),
+              _ => {}
+            }
+          }
+        }
+      }
+    }
+  }
+
+  fn setup(&mut self, res: &mut Resources) {
+    use amethyst::core::specs::prelude::SystemData;
+    Self::SystemData::setup(res);
+    res.fetch_mut::<InputState>().cursor = self.initial_cursor;
+    self.event_reader = Some(res.fetch_mut::<EventChannel<Event>>().register_reader());
+  }
+}
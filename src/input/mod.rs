@@ -0,0 +1,175 @@
+use std::collections::HashSet;
+use amethyst::{
+  core::nalgebra::{ Vector2 },
+  renderer::{ WindowMessages, mouse },
+};
+use winit::{ VirtualKeyCode, MouseButton };
+
+pub mod input_system;
+pub mod config;
+pub use self::config::{ InputConfig, CursorMode };
+
+const DRAG_THRESHOLD: f32 = 4.0;
+
+#[derive(Clone, Copy)]
+pub struct Drag {
+  pub button: MouseButton,
+  pub start: Vector2<f32>,
+  pub current: Vector2<f32>,
+}
+
+pub struct InputState {
+  pub pressed_keys: HashSet<VirtualKeyCode>,
+  pub keys_down: HashSet<VirtualKeyCode>,
+  pub keys_up: HashSet<VirtualKeyCode>,
+  pub pressed_buttons: HashSet<MouseButton>,
+  pub buttons_down: HashSet<MouseButton>,
+  pub buttons_up: HashSet<MouseButton>,
+  pub cursor: Vector2<f32>,
+  pub cursor_delta: Vector2<f32>,
+  pub drag: Option<Drag>,
+  pub wheel_delta: f32,
+  // printable characters typed during this frame
+  pub typed: String,
+  press_position: Option<(MouseButton, Vector2<f32>)>,
+}
+
+impl Default for InputState {
+  fn default() -> Self {
+    InputState {
+      pressed_keys: HashSet::new(),
+      keys_down: HashSet::new(),
+      keys_up: HashSet::new(),
+      pressed_buttons: HashSet::new(),
+      buttons_down: HashSet::new(),
+      buttons_up: HashSet::new(),
+      cursor: Vector2::<f32>::new(0.0, 0.0),
+      cursor_delta: Vector2::<f32>::new(0.0, 0.0),
+      drag: None,
+      wheel_delta: 0.0,
+      typed: String::new(),
+      press_position: None,
+    }
+  }
+}
+
+impl InputState {
+  pub fn is_key_pressed(&self, key: VirtualKeyCode) -> bool {
+    self.pressed_keys.contains(&key)
+  }
+
+  pub fn is_key_down(&self, key: VirtualKeyCode) -> bool {
+    self.keys_down.contains(&key)
+  }
+
+  pub fn is_key_up(&self, key: VirtualKeyCode) -> bool {
+    self.keys_up.contains(&key)
+  }
+
+  pub fn is_button_pressed(&self, button: MouseButton) -> bool {
+    self.pressed_buttons.contains(&button)
+  }
+
+  pub fn is_button_down(&self, button: MouseButton) -> bool {
+    self.buttons_down.contains(&button)
+  }
+
+  pub fn is_button_up(&self, button: MouseButton) -> bool {
+    self.buttons_up.contains(&button)
+  }
+
+  // edges and deltas live exactly one frame
+  fn begin_frame(&mut self) {
+    self.keys_down.clear();
+    self.keys_up.clear();
+    self.buttons_down.clear();
+    self.buttons_up.clear();
+    self.cursor_delta = Vector2::<f32>::new(0.0, 0.0);
+    self.wheel_delta = 0.0;
+    self.typed.clear();
+    if self.press_position.is_none() {
+      self.drag = None;
+    }
+  }
+
+  fn key_pressed(&mut self, key: VirtualKeyCode) {
+    if self.pressed_keys.insert(key) {
+      self.keys_down.insert(key);
+    }
+  }
+
+  fn key_released(&mut self, key: VirtualKeyCode) {
+    if self.pressed_keys.remove(&key) {
+      self.keys_up.insert(key);
+    }
+  }
+
+  fn button_pressed(&mut self, button: MouseButton) {
+    if self.pressed_buttons.insert(button) {
+      self.buttons_down.insert(button);
+      if self.press_position.is_none() {
+        self.press_position = Some((button, self.cursor));
+      }
+    }
+  }
+
+  fn button_released(&mut self, button: MouseButton) {
+    if self.pressed_buttons.remove(&button) {
+      self.buttons_up.insert(button);
+    }
+    if let Some((b, _)) = self.press_position {
+      if b == button {
+        self.press_position = None;
+      }
+    }
+  }
+
+  fn release_all(&mut self) {
+    let keys: Vec<VirtualKeyCode> = self.pressed_keys.iter().cloned().collect();
+    for key in keys {
+      self.key_released(key);
+    }
+    let buttons: Vec<MouseButton> = self.pressed_buttons.iter().cloned().collect();
+    for button in buttons {
+      self.button_released(button);
+    }
+  }
+
+  fn move_cursor(&mut self, position: Vector2<f32>) {
+    self.cursor_delta += position - self.cursor;
+    self.cursor = position;
+    if let Some((button, start)) = self.press_position {
+      let dragged = self.drag.is_some() || (position - start).norm() > DRAG_THRESHOLD;
+      if dragged {
+        self.drag = Some(Drag {
+          button,
+          start,
+          current: position
+        });
+      }
+    }
+  }
+}
+
+pub fn capture_cursor(msg: &mut WindowMessages, mode: CursorMode) {
+  match mode {
+    CursorMode::Virtual => {
+      mouse::grab_cursor(msg);
+      mouse::hide_cursor(msg);
+    },
+    // the OS cursor is the pointer in absolute mode, so it stays visible
+    CursorMode::Absolute => {
+      mouse::release_cursor(msg);
+      show_cursor(msg);
+    },
+  }
+}
+
+pub fn release_cursor(msg: &mut WindowMessages) {
+  mouse::release_cursor(msg);
+  show_cursor(msg);
+}
+
+fn show_cursor(msg: &mut WindowMessages) {
+  msg.send_command(|win| win.hide_cursor(false));
+}
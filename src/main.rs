@@ -6,15 +6,28 @@ mod rendering;
 mod asset_loader;
 mod resource;
 mod cursor;
-mod debug_placement_system;
+mod input;
+mod random;
+mod replay;
+mod cli;
+mod simulation;
+mod ui;
+mod states;
+mod hot_reload;
+mod headless;
+mod scenario;
+mod console;
+mod tools;
 
 use amethyst::{
   prelude::*,
+  config::Config,
   core::{
     TransformBundle,
     nalgebra::{ Vector2 },
   },
   input::{ InputBundle },
+  assets::{ HotReloadBundle, HotReloadStrategy },
   renderer::{
     DisplayConfig,
     DrawFlat2D,
@@ -31,18 +44,63 @@ use gfx::{
   preset::blend
 };
 // use crate::tile_map::tile_map_pass;
-use crate::rendering::tile_map::TileMapPass;
+use crate::rendering::tile_map::{ TileMapPass, AutotileSystem };
+use crate::rendering::fog::FogSystem;
+use crate::rendering::lighting::{ DaylightSystem, LightMapSystem };
+use crate::rendering::weather_overlay::WeatherOverlaySystem;
 use crate::rendering::screen_space::screen_space_pass::ScreenSpacePass;
 use crate::cursor::mouse_pointer_system::MousePointerSystem;
-use crate::debug_placement_system::DebugPlacementSystem;
+use crate::cursor::tile_picking_system::TilePickingSystem;
+use crate::ui::tile_inspector::TileInspectorSystem;
+use crate::ui::pawn_inspector::PawnInspectorSystem;
+use crate::ui::button::ButtonHoverSystem;
+use crate::console::ConsoleSystem;
+use crate::input::input_system::InputSystem;
+use crate::input::InputConfig;
+use crate::tools::ToolSystem;
+use crate::ui::tool_strip::ToolStripSystem;
+use crate::cli::Options;
+use crate::random::GameSeed;
+use crate::replay::Replay;
+use crate::scenario::SelectedScenario;
+use crate::simulation::GameSpeedSystem;
+use crate::ui::speed_widget::SpeedWidgetSystem;
+use crate::ui::text_panel::TextPanelSystem;
+use crate::ui::clock_widget::ClockWidgetSystem;
+use crate::states::MainMenuState;
+use crate::asset_loader::AssetFallbackSystem;
+use crate::hot_reload::DataReloadSystem;
 // use crate::game_state::TileMapTilesKind;
 
 fn main() -> amethyst::Result<()> {
   amethyst::start_logger(Default::default());
+  let options = Options::from_args();
+  let mut seed = options.seed.map(GameSeed).unwrap_or_default();
+  let mut scenario = SelectedScenario(options.scenario.clone());
+  let replay = match (&options.replay, &options.record) {
+    (Some(path), _) => {
+      let (replay, recorded_seed, recorded_scenario) = match Replay::load(path) {
+        Ok(r) => r,
+        Err(e) => {
+          println!("{}", e);
+          return Ok(());
+        }
+      };
+      seed = GameSeed(recorded_seed);
+      scenario = SelectedScenario(recorded_scenario);
+      replay
+    }
+    (None, Some(path)) => Replay::record(path.clone(), seed.0, scenario.0.clone()),
+    (None, None) => Replay::default()
+  };
+  if options.headless {
+    return headless::run(&options, seed, replay, scenario);
+  }
   let app_dir = application_root_dir();
   let path = format!("{}/resources/display_config.ron", app_dir);
 
   let display_config = DisplayConfig::load(path);
+  let input_config = InputConfig::load(format!("{}/resources/input_config.ron", app_dir));
   let (w, h) = display_config.dimensions.unwrap();
   let ss_pass: ScreenSpacePass = Default::default(); 
   let pipe = Pipeline::build()
@@ -59,21 +117,46 @@ fn main() -> amethyst::Result<()> {
   let input_bundle = InputBundle::<String, String>::new(); 
   let screen_dimensions = Vector2::<f32>::new(w as f32, h as f32);
 
+
   let game_data = GameDataBuilder::default()
     .with_bundle(TransformBundle::new())?
     .with_bundle(input_bundle)?
-    .with(MousePointerSystem::new(screen_dimensions/2.0), "mouse_pointer_system", &["input_system"])
-    .with(DebugPlacementSystem::new(), "debug_placement_system", &["mouse_pointer_system"])
+    .with(InputSystem::new(screen_dimensions/2.0), "input_state_system", &["input_system"])
+    .with(MousePointerSystem::new(), "mouse_pointer_system", &["input_state_system"])
+    .with(ButtonHoverSystem, "button_hover_system", &["input_state_system"])
+    .with(TilePickingSystem, "tile_picking_system", &["button_hover_system"])
+    .with(ToolStripSystem, "tool_strip_system", &["button_hover_system"])
+    .with(ToolSystem, "tool_system", &["tile_picking_system", "tool_strip_system"])
+    .with(PawnInspectorSystem, "pawn_inspector_system", &["tile_picking_system"])
+    // runs after the pawn inspector, right clicks meant for pawns do not pin tiles
+    .with(TileInspectorSystem, "tile_inspector_system", &["pawn_inspector_system"])
+    .with(AutotileSystem::default(), "autotile_system", &[])
+    .with(FogSystem, "fog_system", &[])
+    .with(LightMapSystem, "light_map_system", &[])
+    .with(DaylightSystem, "daylight_system", &["light_map_system"])
+    .with(ClockWidgetSystem, "clock_widget_system", &[])
+    .with(WeatherOverlaySystem::new(), "weather_overlay_system", &[])
+    .with(ConsoleSystem, "console_system", &["input_state_system"])
+    .with(GameSpeedSystem::new(), "game_speed_system", &["console_system"])
+    .with(SpeedWidgetSystem, "speed_widget_system", &["game_speed_system"])
+    .with(TextPanelSystem::default(), "text_panel_system", &[])
+    .with(AssetFallbackSystem, "asset_fallback_system", &["speed_widget_system"])
+    .with(DataReloadSystem::new(), "data_reload_system", &[])
+    .with_bundle(HotReloadBundle::new(HotReloadStrategy::every(30)))?
     .with_bundle(
       RenderBundle::new(pipe, Some(display_config))
         .with_sprite_sheet_processor()
     )?;
   
 
-  let mut game = Application::new(
+  let mut game = Application::build(
     app_dir, 
-    game_state::State::new(w as u16, h as u16), 
-    game_data)?;
+    MainMenuState::new(w as u16, h as u16))?
+    .with_resource(input_config)
+    .with_resource(seed)
+    .with_resource(replay)
+    .with_resource(scenario)
+    .build(game_data)?;
   game.run();
 
   Ok(())
@@ -0,0 +1,25 @@
+use amethyst::ecs::{ Component, DenseVecStorage };
+use serde_derive::{ Serialize, Deserialize };
+use super::Spece;
+
+// sides sharing what they see, the player controls the colony
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Faction {
+  Colony,
+  Wild,
+}
+
+pub const PLAYER_FACTION: Faction = Faction::Colony;
+
+impl Faction {
+  pub fn of(spece: &Spece) -> Self {
+    match spece {
+      Spece::Human(_, _, _) => Faction::Colony,
+      Spece::Wolf(_) => Faction::Wild,
+    }
+  }
+}
+
+impl Component for Faction {
+  type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,55 @@
+use amethyst::ecs::{ Component, DenseVecStorage };
+use serde_derive::{ Serialize, Deserialize };
+use crate::random::{ GameSeed, Rng };
+
+// stable between runs, unlike entities, so messages and replays can refer to pawns
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PawnId(pub u32);
+
+impl Component for PawnId {
+  type Storage = DenseVecStorage<Self>;
+}
+
+pub struct Name(pub String);
+
+impl Component for Name {
+  type Storage = DenseVecStorage<Self>;
+}
+
+const SYLLABLES: [&str; 16] = [
+  "ug", "ma", "ka", "ro", "tu", "gra", "na", "ok",
+  "zu", "lo", "bar", "ri", "sha", "mo", "te", "ak",
+];
+
+pub struct PawnIdentities {
+  next_id: u32,
+  rng: Rng,
+}
+
+impl Default for PawnIdentities {
+  fn default() -> Self {
+    PawnIdentities::new(&GameSeed::default())
+  }
+}
+
+impl PawnIdentities {
+  pub fn new(seed: &GameSeed) -> Self {
+    PawnIdentities {
+      next_id: 0,
+      rng: Rng::from_seed(seed, 3),
+    }
+  }
+
+  pub fn next(&mut self) -> (PawnId, Name) {
+    let id = PawnId(self.next_id);
+    self.next_id += 1;
+    let length = self.rng.range(2, 4);
+    let mut name: String = (0..length)
+      .map(|_| SYLLABLES[self.rng.range(0, SYLLABLES.len() as i32) as usize])
+      .collect();
+    if let Some(first) = name.get_mut(0..1) {
+      first.make_ascii_uppercase();
+    }
+    (id, Name(name))
+  }
+}
@@ -1,10 +1,14 @@
 mod view_properties;
 pub mod sprites;
+pub mod identity;
+pub mod faction;
 use amethyst::{
   core::transform::{ Transform },
-  core::nalgebra::{ Vector2 },
+  core::nalgebra::{ Vector2, Vector3 },
   ecs::{ 
     Builder, 
+    Entity,
+    Join,
     World, 
   },
   renderer::{ 
@@ -15,40 +19,73 @@ use self::sprites::SpriteCollection;
 
 pub use self::view_properties::{ Sex, Race, Complex, Spece };
 use crate::rendering::tile_map::{ Tile };
+use crate::simulation::{ Needs, Movement, Jobs, Health };
+use crate::resource::Inventory;
+use crate::random::GameSeed;
+pub use self::identity::{ PawnId, Name, PawnIdentities };
+pub use self::faction::{ Faction, PLAYER_FACTION };
 
-pub fn place_debug_pawn(world: &mut World, spece: Spece, mut wher: Vector2<f32>, tile: Tile) {
-  let mut transform: Transform = Default::default();
+const PAWN_SCALE: f32 = 0.15;
+
+pub fn pawn_translation(mut wher: Vector2<f32>) -> Vector3<f32> {
   let mut shift = Vector2::<f32>::new(0.0, 128.);
-  let scale = 0.15;
-  shift *= scale;
+  shift *= PAWN_SCALE;
   wher -= shift;
+  Vector3::<f32>::new(wher.x, wher.y, 0.9)
+}
 
-  transform.set_scale(scale, scale, 1.0);
-  transform.set_xyz(wher.x, wher.y, 0.9);
+pub fn place_debug_pawn(world: &mut World, spece: Spece, wher: Vector2<f32>, tile: Tile) {
+  place_pawn(world, spece, wher, tile);
+}
+
+pub fn place_pawn(world: &mut World, spece: Spece, wher: Vector2<f32>, tile: Tile) -> Entity {
+  let mut transform: Transform = Default::default();
+  let translation = pawn_translation(wher);
+
+  transform.set_scale(PAWN_SCALE, PAWN_SCALE, 1.0);
+  transform.set_xyz(translation.x, translation.y, translation.z);
 
   world.register::<Spece>();
-  let sprite: Option<SpriteRender> = {
-    println!("get collection");
-    let sprite_collection = world.res.fetch_mut::<SpriteCollection>();
-    let spr = sprite_collection.sprites.get(&spece);
-    match spr {
-      Some(s) => Some(s.clone()),
-      _ => None,
-    }
+  world.register::<Needs>();
+  world.register::<Movement>();
+  world.register::<Jobs>();
+  world.register::<Inventory>();
+  world.register::<PawnId>();
+  world.register::<Name>();
+  world.register::<Faction>();
+  world.register::<Health>();
+  let (id, name) = {
+    let seed = *world.res.entry::<GameSeed>().or_insert_with(GameSeed::default);
+    world.res.entry::<PawnIdentities>().or_insert_with(|| PawnIdentities::new(&seed)).next()
   };
-  
+  let sprite: Option<SpriteRender> = world.res.try_fetch::<SpriteCollection>()
+    .and_then(|collection| collection.sprites.get(&spece).cloned());
+
+  let pawn = world.create_entity()
+    .with(transform)
+    .with(spece)
+    .with(tile)
+    .with(Needs::default())
+    .with(Movement::default())
+    .with(Jobs::default())
+    .with(Inventory::default())
+    .with(id)
+    .with(name)
+    .with(Faction::of(&spece))
+    .with(Health::default());
+  // headless simulation has no sprites at all
   match sprite {
-    Some(s) => {
-      println!("do placement");
-      world.create_entity()
-        .with(transform)
-        .with(spece)
-        .with(tile)
-        .with(s)
-        .build();
-    }
-    _ => return
-  };
+    Some(s) => pawn.with(s).build(),
+    None => pawn.build()
+  }
+}
+
+pub fn find_pawn(world: &World, id: PawnId) -> Option<Entity> {
+  let entities = world.entities();
+  let ids = world.read_storage::<PawnId>();
+  (&entities, &ids).join()
+    .find(|(_, i)| **i == id)
+    .map(|(e, _)| e)
 }
 
 pub fn register(world: &mut World) {
@@ -34,5 +34,18 @@ impl SpriteCollection {
       sprite_sheet: sprite_sheet_handle,
       sprite_number: 0
     });
+
+    let handle = load_png_texture(world, "resources/sprites/pawns/wolf/front.png".to_string());
+    let sprite_sheet_handle = load_ss_asset(
+      world,
+      "resources/sprites/pawns/wolf/sprite_sheet.ron".to_string(),
+      handle
+    );
+    for sex in [Sex::Male, Sex::Female].iter() {
+      self.sprites.insert(Spece::Wolf(*sex), SpriteRender {
+        sprite_sheet: sprite_sheet_handle.clone(),
+        sprite_number: 0
+      });
+    }
   }
 }
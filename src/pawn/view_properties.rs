@@ -1,6 +1,7 @@
 use amethyst::ecs::{ Component, DenseVecStorage };
+use serde_derive::{ Serialize, Deserialize };
 
-#[derive(Hash, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Race {
   Euro,
   Afro,
@@ -8,26 +9,35 @@ pub enum Race {
   Indean,
 }
 
-#[derive(Hash, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Complex {
   Skinny,
   Obese,
   Athletic
 }
 
-#[derive(Hash, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Sex {
   Male,
   Female
 }
 
 
-#[derive(Hash, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Spece {
   Wolf(Sex),
   Human(Sex, Race, Complex)
 }
 
+impl Spece {
+  pub fn is_animal(&self) -> bool {
+    match self {
+      Spece::Wolf(_) => true,
+      Spece::Human(_, _, _) => false,
+    }
+  }
+}
+
 impl Component for Spece {
   type Storage = DenseVecStorage<Self>;
 }
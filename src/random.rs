@@ -0,0 +1,56 @@
+use std::time::{ SystemTime, UNIX_EPOCH };
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameSeed(pub u64);
+
+impl Default for GameSeed {
+  fn default() -> Self {
+    let now = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs())
+      .unwrap_or(0);
+    GameSeed(now)
+  }
+}
+
+// xorshift64*, small and stable across platforms so replays stay deterministic
+#[derive(Clone, Copy, Debug)]
+pub struct Rng {
+  state: u64,
+}
+
+impl Rng {
+  pub fn new(seed: u64) -> Self {
+    Rng {
+      state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed }
+    }
+  }
+
+  pub fn from_seed(seed: &GameSeed, stream: u64) -> Self {
+    Rng::new(seed.0 ^ stream.wrapping_mul(0x2545_F491_4F6C_DD1D))
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    let mut x = self.state;
+    x ^= x >> 12;
+    x ^= x << 25;
+    x ^= x >> 27;
+    self.state = x;
+    x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+  }
+
+  pub fn next_f32(&mut self) -> f32 {
+    (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+  }
+
+  pub fn range(&mut self, from: i32, to: i32) -> i32 {
+    if to <= from {
+      return from;
+    }
+    from + (self.next_u64() % (to - from) as u64) as i32
+  }
+
+  pub fn chance(&mut self, probability: f32) -> bool {
+    self.next_f32() < probability
+  }
+}
@@ -0,0 +1,36 @@
+use amethyst::{
+  core::Hidden,
+  ecs::{ Entities, Join, Read, ReadStorage, System, WriteStorage },
+};
+use crate::pawn::{ Faction, PLAYER_FACTION };
+use crate::rendering::tile_map::Tile;
+use crate::simulation::{ Visibility, TileVisibility };
+
+// hides pawns of other factions standing where the player does not see right now
+pub struct FogSystem;
+
+impl<'a> System<'a> for FogSystem {
+  type SystemData = (
+    Read<'a, Visibility>,
+    Entities<'a>,
+    ReadStorage<'a, Tile>,
+    ReadStorage<'a, Faction>,
+    WriteStorage<'a, Hidden>,
+  );
+
+  fn run(&mut self, (visibility, entities, tiles, factions, mut hidden): Self::SystemData) {
+    for (e, tile, faction) in (&entities, &tiles, &factions).join() {
+      let hide = *faction != PLAYER_FACTION && visibility.of(PLAYER_FACTION, tile) != TileVisibility::Visible;
+      if hide == hidden.contains(e) {
+        continue;
+      }
+      if hide {
+        if let Err(err) = hidden.insert(e, Hidden) {
+          println!("cannot hide pawn: {}", err);
+        }
+      } else {
+        hidden.remove(e);
+      }
+    }
+  }
+}
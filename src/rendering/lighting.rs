@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::hash::{ Hash, Hasher };
+use std::collections::hash_map::DefaultHasher;
+use amethyst::{
+  core::nalgebra::Vector3,
+  ecs::{ Component, DenseVecStorage, Entities, Join, Read, ReadStorage, System, Write, WriteStorage },
+  renderer::{ Rgba, SpriteRender },
+};
+use crate::simulation::Clock;
+use crate::rendering::tile_map::{ Tile, TileMap };
+
+// light of the sky, the tile map pass multiplies tiles by it
+pub struct AmbientLight {
+  pub color: [f32; 3],
+  pub intensity: f32,
+}
+
+impl Default for AmbientLight {
+  fn default() -> Self {
+    AmbientLight {
+      color: [1.0, 1.0, 1.0],
+      intensity: 1.0,
+    }
+  }
+}
+
+impl AmbientLight {
+  pub fn tint(&self) -> Rgba {
+    let c = self.color;
+    Rgba(c[0] * self.intensity, c[1] * self.intensity, c[2] * self.intensity, 1.0)
+  }
+}
+
+// light emitted from the tile of the entity, fading out towards the radius
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightSource {
+  pub color: [f32; 3],
+  pub intensity: f32,
+  pub radius: i32,
+}
+
+impl Component for LightSource {
+  type Storage = DenseVecStorage<Self>;
+}
+
+// sum of point lights per tile, added on top of the sky light
+#[derive(Default)]
+pub struct LightMap {
+  pub tiles: HashMap<Vector3<i32>, [f32; 3]>,
+  sources_hash: u64,
+}
+
+impl LightMap {
+  pub fn at(&self, tile: &Tile) -> [f32; 3] {
+    self.tiles.get(&tile.position).cloned().unwrap_or([0.0; 3])
+  }
+}
+
+// rebuilds the light map when some light source was added, removed or moved
+pub struct LightMapSystem;
+
+impl<'a> System<'a> for LightMapSystem {
+  type SystemData = (
+    Write<'a, LightMap>,
+    ReadStorage<'a, TileMap>,
+    ReadStorage<'a, Tile>,
+    ReadStorage<'a, LightSource>,
+  );
+
+  fn run(&mut self, (mut light_map, maps, tiles, sources): Self::SystemData) {
+    let topology = (&maps).join().next().map(|tm| tm.topology).unwrap_or_default();
+    let mut sources: Vec<(&Tile, &LightSource)> = (&tiles, &sources).join().collect();
+    sources.sort_by_key(|(t, _)| (t.position.x, t.position.y));
+    let mut hasher = DefaultHasher::new();
+    topology.hash(&mut hasher);
+    for (tile, source) in sources.iter() {
+      tile.hash(&mut hasher);
+      source.radius.hash(&mut hasher);
+      source.intensity.to_bits().hash(&mut hasher);
+      for c in source.color.iter() {
+        c.to_bits().hash(&mut hasher);
+      }
+    }
+    let hash = hasher.finish();
+    if hash == light_map.sources_hash {
+      return;
+    }
+    light_map.sources_hash = hash;
+    light_map.tiles.clear();
+    for (at, source) in sources {
+      for tile in topology.area(at, source.radius) {
+        let falloff = 1.0 - topology.distance(at, &tile) as f32 / (source.radius + 1) as f32;
+        let light = light_map.tiles.entry(tile.position).or_insert([0.0; 3]);
+        for i in 0..3 {
+          light[i] = (light[i] + source.color[i] * source.intensity * falloff).min(1.0);
+        }
+      }
+    }
+  }
+}
+
+// Tint of a sprite in full daylight. Sprites tinted before they are first lit keep that tint here,
+// later changes of the tint go to this component, Rgba is overwritten by the light.
+pub struct BaseTint(pub Rgba);
+
+impl Component for BaseTint {
+  type Storage = DenseVecStorage<Self>;
+}
+
+fn lit(base: Rgba, light: Rgba) -> Rgba {
+  Rgba(base.0 * light.0, base.1 * light.1, base.2 * light.2, base.3)
+}
+
+// Follows the clock. Sprites are drawn by the stock flat pass, so they get the light as a tint,
+// with point lights of their tile added.
+pub struct DaylightSystem;
+
+impl<'a> System<'a> for DaylightSystem {
+  type SystemData = (
+    Read<'a, Clock>,
+    Write<'a, AmbientLight>,
+    Read<'a, LightMap>,
+    Entities<'a>,
+    ReadStorage<'a, SpriteRender>,
+    ReadStorage<'a, Tile>,
+    WriteStorage<'a, BaseTint>,
+    WriteStorage<'a, Rgba>,
+  );
+
+  fn run(&mut self, (clock, mut light, light_map, entities, sprites, tiles, mut bases, mut tints): Self::SystemData) {
+    let (color, intensity) = clock.light();
+    light.color = color;
+    light.intensity = intensity;
+    for (e, _, tile) in (&entities, &sprites, (&tiles).maybe()).join() {
+      let mut sky = light.tint();
+      if let Some(tile) = tile {
+        let local = light_map.at(tile);
+        sky = Rgba((sky.0 + local[0]).min(1.0), (sky.1 + local[1]).min(1.0), (sky.2 + local[2]).min(1.0), 1.0);
+      }
+      let base = match bases.get(e) {
+        Some(b) => b.0,
+        None => {
+          let b = tints.get(e).cloned().unwrap_or(Rgba(1.0, 1.0, 1.0, 1.0));
+          if let Err(err) = bases.insert(e, BaseTint(b)) {
+            println!("cannot keep sprite tint: {}", err);
+          }
+          b
+        }
+      };
+      let tint = lit(base, sky);
+      if tints.get(e) == Some(&tint) {
+        continue;
+      }
+      if let Err(err) = tints.insert(e, tint) {
+        println!("cannot tint sprite: {}", err);
+      }
+    }
+  }
+}
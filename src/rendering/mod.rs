@@ -1,3 +1,6 @@
 pub mod screen_space;
 pub mod tile_map;
 pub mod camera_getter;
+pub mod fog;
+pub mod lighting;
+pub mod weather_overlay;
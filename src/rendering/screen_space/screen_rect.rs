@@ -9,7 +9,8 @@ pub struct ScreenRect{
   pub size: Vector2<f32>,
   pub flip_x: bool,
   pub flip_y: bool,
-
+  // rects with bigger layer are drawn on top
+  pub layer: u8,
 }
 
 impl Default for ScreenRect {
@@ -19,6 +20,7 @@ impl Default for ScreenRect {
       size: Vector2::<f32>::new(24.0, 24.0),
       flip_x: false,
       flip_y: false,
+      layer: 0,
     }
   }
 }
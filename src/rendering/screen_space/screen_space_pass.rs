@@ -1,6 +1,7 @@
 use amethyst::{
   assets::{ AssetStorage },
   core::{
+    Hidden,
     nalgebra::{ Vector2, Matrix3 },
     specs::{ 
       prelude::{
@@ -41,12 +42,17 @@ use gfx::{
 };
 use nalgebra_glm::{ translation2d, scaling2d };
 
+use crate::hot_reload::load_shader;
 use super::screen_rect::{ 
   Transform2D, ScreenRect //, TextureForScreenItem
 };
 
 const VERT_SHADER: &[u8] = include_bytes!("../../../resources/shaders/screen_space/vert.glsl");
 const FRAG_SHADER: &[u8] = include_bytes!("../../../resources/shaders/screen_space/frag.glsl");
+const SHADER_PATHS: [&str; 2] = [
+  "./resources/shaders/screen_space/vert.glsl",
+  "./resources/shaders/screen_space/frag.glsl",
+];
 
 pub struct ScreenSpacePass {
   buffer: Option<Buffer<Resources, i32>>
@@ -96,15 +102,18 @@ impl<'a> PassData<'a> for ScreenSpacePass {
     Read<'a, AssetStorage<Texture>>,
     ReadStorage<'a, TextureHandle>,
     ReadStorage<'a, Transform2D>,
+    ReadStorage<'a, Hidden>,
     Option<Read<'a, ScreenDimensions>>
   );
 }
 
 impl Pass for ScreenSpacePass {
   fn compile(&mut self, effect: NewEffect<'_>) -> Result<Effect> {
+    let vert = load_shader(SHADER_PATHS[0], VERT_SHADER);
+    let frag = load_shader(SHADER_PATHS[1], FRAG_SHADER);
 
     effect
-      .simple(VERT_SHADER, FRAG_SHADER)
+      .simple(&vert, &frag)
       .with_primitive_type(Primitive::TriangleList)
       .with_texture("sprite")
       .with_raw_global("transform2D")
@@ -131,6 +140,7 @@ impl Pass for ScreenSpacePass {
       texture_storage,
       texture_handle_storage,
       transform2D_storage,
+      hidden_storage,
       screen_dimensions
     ): <Self as PassData<'a>>::Data
   ) {
@@ -146,7 +156,11 @@ impl Pass for ScreenSpacePass {
     };
     let projection_matrix: [[f32; 3]; 3] = prepare2d_projection(dimensions[0], dimensions[1]).into();
 
-    for (screen_rect, texture_handle, transform) in  (&screen_rect_storage, &texture_handle_storage, &transform2D_storage).join() {
+    let mut rects: Vec<_> = (&screen_rect_storage, &texture_handle_storage, &transform2D_storage, !&hidden_storage).join()
+      .map(|(screen_rect, texture_handle, transform, _)| (screen_rect, texture_handle, transform))
+      .collect();
+    rects.sort_by_key(|(screen_rect, _, _)| screen_rect.layer);
+    for (screen_rect, texture_handle, transform) in rects {
       let p: [f32; 4] = [
         screen_rect.position.x,
         screen_rect.position.y,
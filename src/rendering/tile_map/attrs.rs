@@ -12,17 +12,18 @@ use gfx::{
   traits::{ Pod },
 };
 
+// x, y, index into the tile props texture, elevation
 struct PositionWithId;
 
 impl Attribute for PositionWithId {
   const NAME: &'static str = "position_id";
-  const FORMAT: Format  = Format(SurfaceType::R32_G32_B32, ChannelType::Int);
-  const SIZE: u32 = 12;
-  type Repr = [i32; 3];
+  const FORMAT: Format  = Format(SurfaceType::R32_G32_B32_A32, ChannelType::Int);
+  const SIZE: u32 = 16;
+  type Repr = [i32; 4];
 }
 
 pub struct TileMapAttributes {
-  _point: [i32; 3]
+  _point: [i32; 4]
 }
 unsafe impl Pod for TileMapAttributes {}
 
@@ -0,0 +1,151 @@
+use std::collections::{ HashMap, HashSet };
+use std::hash::{ Hash, Hasher };
+use amethyst::{
+  core::{ nalgebra::{ Vector2, Vector3 }, shrev::ReaderId },
+  ecs::{ Component, DenseVecStorage, Entities, Entity, Join, ReadStorage, System, SystemData, WriteStorage, Resources },
+  ecs::storage::ComponentEvent,
+};
+use crate::game_state::TileMapTilesKind;
+use super::entity::tile_sprite;
+use super::tile::Tile;
+use super::tile_map::TileMap;
+use super::topology::GridTopology;
+
+// Neighbouring kind drawn over the edges of the tile. Bit i of the mask is set when
+// the edge from corner i to corner i + 1 borders the overlay kind, the fragment shader
+// fades the overlay sprite from those edges towards the center.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TileTransition {
+  pub overlay: Vector2<f32>,
+  pub mask: u8,
+}
+
+impl Component for TileTransition {
+  type Storage = DenseVecStorage<Self>;
+}
+
+impl Hash for TileTransition {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.overlay.x.to_bits().hash(state);
+    self.overlay.y.to_bits().hash(state);
+    self.mask.hash(state);
+  }
+}
+
+// kinds with higher priority spill over the edges of lower ones
+fn priority(kind: TileMapTilesKind) -> i32 {
+  match kind {
+    TileMapTilesKind::Water | TileMapTilesKind::Ice => 0,
+    TileMapTilesKind::Dirt => 1,
+    TileMapTilesKind::Grass => 2,
+  }
+}
+
+// only the strongest neighbouring kind is blended in, one overlay per tile keeps the shader simple
+pub fn transition(kind: TileMapTilesKind, edges: &[Option<TileMapTilesKind>]) -> TileTransition {
+  let overlay = edges.iter()
+    .filter_map(|e| *e)
+    .filter(|e| priority(*e) > priority(kind))
+    .max_by_key(|e| priority(*e));
+  match overlay {
+    None => TileTransition::default(),
+    Some(overlay) => TileTransition {
+      overlay: tile_sprite(overlay).offset,
+      mask: edges.iter()
+        .enumerate()
+        .filter(|(_, e)| **e == Some(overlay))
+        .fold(0, |mask, (i, _)| mask | 1 << i),
+    }
+  }
+}
+
+// Listens to changes of tile kinds, so only the changed tiles and their neighbours are recomputed.
+// Kinds must be changed through insert or get_mut of single tiles, a mutable join flags them all.
+#[derive(Default)]
+pub struct AutotileSystem {
+  reader: Option<ReaderId<ComponentEvent>>,
+  // tiles by position, and positions by entity id, for neighbours of removed tiles
+  index: HashMap<Vector3<i32>, Entity>,
+  positions: HashMap<u32, Vector3<i32>>,
+  topology: Option<GridTopology>,
+}
+
+impl<'a> System<'a> for AutotileSystem {
+  type SystemData = (
+    Entities<'a>,
+    ReadStorage<'a, TileMap>,
+    ReadStorage<'a, Tile>,
+    ReadStorage<'a, TileMapTilesKind>,
+    WriteStorage<'a, TileTransition>,
+  );
+
+  fn run(&mut self, (entities, maps, tiles, kinds, mut transitions): Self::SystemData) {
+    let mut changed: Vec<Vector3<i32>> = Vec::new();
+    let events: Vec<ComponentEvent> = match &mut self.reader {
+      None => return,
+      Some(reader) => kinds.channel().read(reader).cloned().collect()
+    };
+    for event in events {
+      match event {
+        ComponentEvent::Inserted(id) | ComponentEvent::Modified(id) => {
+          let entity = entities.entity(id);
+          if let Some(tile) = tiles.get(entity) {
+            self.index.insert(tile.position, entity);
+            self.positions.insert(id, tile.position);
+            changed.push(tile.position);
+          }
+        }
+        ComponentEvent::Removed(id) => {
+          if let Some(position) = self.positions.remove(&id) {
+            if self.index.get(&position).map(|e| e.id()) == Some(id) {
+              self.index.remove(&position);
+            }
+            changed.push(position);
+          }
+        }
+      }
+    }
+    let topology = match (&maps).join().next() {
+      None => return,
+      Some(tm) => tm.topology
+    };
+    // edges differ between topologies, every tile is recomputed
+    if self.topology != Some(topology) {
+      self.topology = Some(topology);
+      changed = self.index.keys().cloned().collect();
+    }
+    if changed.is_empty() {
+      return;
+    }
+
+    let mut dirty: HashSet<Vector3<i32>> = HashSet::new();
+    for position in changed {
+      let tile = Tile::new(position.x, position.y);
+      dirty.insert(position);
+      dirty.extend(topology.edge_neighbours(&tile).iter().map(|n| n.position));
+    }
+    let kind_at = |p: &Vector3<i32>| self.index.get(p).and_then(|e| kinds.get(*e)).cloned();
+    for position in dirty {
+      let (entity, kind) = match self.index.get(&position).and_then(|e| kinds.get(*e).map(|k| (*e, *k))) {
+        None => continue,
+        Some(found) => found
+      };
+      let edges: Vec<Option<TileMapTilesKind>> = topology.edge_neighbours(&Tile::new(position.x, position.y))
+        .iter()
+        .map(|n| kind_at(&n.position))
+        .collect();
+      let t = transition(kind, &edges);
+      if transitions.get(entity) == Some(&t) {
+        continue;
+      }
+      if let Err(e) = transitions.insert(entity, t) {
+        println!("cannot update tile transition: {}", e);
+      }
+    }
+  }
+
+  fn setup(&mut self, res: &mut Resources) {
+    Self::SystemData::setup(res);
+    self.reader = Some(WriteStorage::<TileMapTilesKind>::fetch(res).register_reader());
+  }
+}
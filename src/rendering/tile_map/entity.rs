@@ -1,68 +1,127 @@
 use amethyst::core::nalgebra::{ Vector2, Vector3 };
-use nalgebra_glm::{ vec2, vec3 };
+use nalgebra_glm::{ vec2 };
 use amethyst::prelude::{ World, Builder };
+use amethyst::ecs::{ Entity, Join };
 
-use super::tile_map::{ TextureInfo, TileMap, hex_basis };
-use super::tile::{ Tile, TileSprite };
+use super::tile_map::{ TextureInfo, TileMap };
+use super::topology::GridTopology;
+use super::tile::{ Tile, TileSprite, Elevation };
 use crate::asset_loader::load_png_texture;
+use crate::game_state::TileMapTilesKind;
 
 pub fn create_debug_tile_map(world: &mut World, size: u16, texture_path: String) {
-  let center: Vector3<i32> = Vector3::new(0, 0, 0); 
+  let tile_map = create_tile_map(world, GridTopology::default(), size, &mut |_| TileMapTilesKind::Grass);
+  attach_tile_map_texture(world, tile_map, texture_path);
+}
+
+pub fn attach_tile_map_texture(world: &mut World, tile_map: Entity, texture_path: String) {
   let t = load_png_texture(world, texture_path);
   let t = TextureInfo {
     texture: t,
     size: Vector2::new(1024, 1024)
   };
 
-  let scale = Vector2::<f32>::new(20., 10.);
-  let tm = TileMap {
-    scale,
-    basis: hex_basis(scale)
-  };
+  world.register::<TextureInfo>();
+  if let Err(e) = world.write_storage::<TextureInfo>().insert(tile_map, t) {
+    println!("cannot attach texture to tile map: {}", e);
+  }
+}
+
+fn tile_map_of(topology: GridTopology) -> TileMap {
+  TileMap::new(topology, Vector2::<f32>::new(20., 10.))
+}
+
+// map without any texture, enough for simulation to run headless
+pub fn create_tile_map(
+  world: &mut World,
+  topology: GridTopology,
+  size: u16,
+  kind_of: &mut FnMut(&Vector3<i32>) -> TileMapTilesKind
+) -> Entity {
+  let center: Vector3<i32> = Vector3::new(0, 0, 0); 
+  let tm = tile_map_of(topology);
 
-  create_debug_tiles(world, &tm, size, center);
+  create_tiles(world, &tm, size, center, kind_of);
   world.register::<TileMap>();
-  world.register::<TextureInfo>();
   world.create_entity()
-    .with(t)
     .with(tm)
-    .build();
+    .build()
+}
+
+pub fn tile_elevation(world: &World, tile: &Tile) -> Option<i32> {
+  let tiles = world.read_storage::<Tile>();
+  let elevations = world.read_storage::<Elevation>();
+  let found = (&tiles, &elevations).join()
+    .find(|(t, _)| t.position == tile.position)
+    .map(|(_, e)| e.0);
+  found
+}
+
+// world position where things standing on the tile are put
+pub fn tile_center(world: &World, tile: &Tile) -> Option<Vector2<f32>> {
+  let elevation = tile_elevation(world, tile).unwrap_or(0);
+  let maps = world.read_storage::<TileMap>();
+  let center = (&maps).join().next().map(|tm| tm.tile_center(tile, elevation));
+  center
+}
+
+// map with no tiles, they are added one by one with create_tile
+pub fn create_empty_tile_map(world: &mut World, topology: GridTopology) -> Entity {
+  world.register::<TileMap>();
+  world.create_entity()
+    .with(tile_map_of(topology))
+    .build()
+}
+
+pub fn create_tile(world: &mut World, position: Vector3<i32>, kind: TileMapTilesKind, elevation: Elevation) -> Entity {
+  world.register::<Tile>();
+  world.register::<TileSprite>();
+  world.register::<TileMapTilesKind>();
+  world.register::<Elevation>();
+  world.create_entity()
+    .with(Tile {
+      position
+    })
+    .with(tile_sprite(kind))
+    .with(kind)
+    .with(elevation)
+    .build()
 }
 
 pub fn create_debug_tiles(
   world: &mut World,
-  _tile_map: &TileMap,
+  tile_map: &TileMap,
   size: u16,
-  mut center_tile: Vector3<i32>
+  center_tile: Vector3<i32>
 ) {
-  let side = size as i32;
-  let start: i32 = -(side - 1);
-  let end: i32 = side;
-  if (center_tile.x + center_tile.y + center_tile.z) != 0 {
-    center_tile.z = -center_tile.x - center_tile.y;
-  }
-  let tile_size = 1.0 / 4.0;
+  create_tiles(world, tile_map, size, center_tile, &mut |_| TileMapTilesKind::Grass);
+}
 
-  world.register::<Tile>();
-  world.register::<TileSprite>();
+// atlas holds 4x4 tiles, each kind takes a tile in the first row
+pub fn tile_sprite(kind: TileMapTilesKind) -> TileSprite {
+  let tile_size = 1.0 / 4.0;
+  let column = match kind {
+    TileMapTilesKind::Grass => 0.0,
+    TileMapTilesKind::Water => 1.0,
+    TileMapTilesKind::Dirt => 2.0,
+    TileMapTilesKind::Ice => 3.0,
+  };
+  TileSprite {
+    offset: vec2(column * tile_size, 0.0),
+    size: vec2(tile_size, tile_size)
+  }
+}
 
-  for x in start..end {
-    for y in start..end {
-      for z in start..end {
-        let t = vec3(x, y, z) + center_tile;
-        if (t.x + t.y + t.z) == 0 {
-          
-          world.create_entity()
-            .with(Tile {
-              position: t
-            })
-            .with(TileSprite {
-              offset: vec2(0.0, 0.0),
-              size: vec2(tile_size, tile_size)
-            })
-            .build();
-        }
-      }
-    }
+pub fn create_tiles(
+  world: &mut World,
+  tile_map: &TileMap,
+  size: u16,
+  center_tile: Vector3<i32>,
+  kind_of: &mut FnMut(&Vector3<i32>) -> TileMapTilesKind
+) {
+  let center = Tile::new(center_tile.x, center_tile.y);
+  for tile in tile_map.topology.area(&center, size as i32 - 1) {
+    let kind = kind_of(&tile.position);
+    create_tile(world, tile.position, kind, Elevation::default());
   }
 }
@@ -6,7 +6,20 @@ pub mod pass;
 pub mod attrs;
 pub mod tile_map;
 pub mod camera;
-pub use self::entity::create_debug_tile_map;
+pub mod topology;
+pub mod autotile;
+pub use self::entity::{
+  create_debug_tile_map,
+  create_tile_map,
+  create_empty_tile_map,
+  create_tile,
+  tile_center,
+  tile_elevation,
+  attach_tile_map_texture,
+  tile_sprite,
+};
 pub use self::tile::*;
-pub use self::tile_map::TileMap;
+pub use self::tile_map::{ TileMap, ELEVATION_STEP };
+pub use self::topology::GridTopology;
 pub use self::pass::TileMapPass;
+pub use self::autotile::{ AutotileSystem, TileTransition };
@@ -37,6 +37,11 @@ use shred_derive::*;
 const VERT_SHADER: &[u8] = include_bytes!("../../../resources/shaders/tile_map/vertex.glsl");
 const GEOM_SHADER: &[u8] = include_bytes!("../../../resources/shaders/tile_map/geom.glsl");
 const FRAG_SHADER: &[u8] = include_bytes!("../../../resources/shaders/tile_map/frag.glsl");
+const SHADER_PATHS: [&str; 3] = [
+  "./resources/shaders/tile_map/vertex.glsl",
+  "./resources/shaders/tile_map/geom.glsl",
+  "./resources/shaders/tile_map/frag.glsl",
+];
 
 struct LocalTexture {
   sampler: Sampler<Resources>,
@@ -70,11 +75,16 @@ impl LastRender {
 
 
 
-use super::tile_map::{ TileMap, TextureInfo };
-use super::tile::{ Tile, TileSprite };
+use super::tile_map::{ TileMap, TextureInfo, ELEVATION_STEP };
+use super::tile::{ Tile, TileSprite, Elevation };
+use super::autotile::TileTransition;
+use crate::pawn::PLAYER_FACTION;
+use crate::simulation::{ Visibility, TileVisibility };
+use crate::rendering::lighting::{ AmbientLight, LightMap };
 use super::attrs::{ TileMapAttributes };
 use super::camera::{ CameraProperties, set_camera_uniforms };
 use crate::rendering::camera_getter::get_camera;
+use crate::hot_reload::load_shader;
 
 #[derive(Eq, PartialEq)]
 pub struct LastRender {
@@ -92,15 +102,40 @@ pub struct TileMapPassData<'a> {
   texture_info: ReadStorage<'a, TextureInfo>,
   tiles: ReadStorage<'a, Tile>,
   tile_sprites: ReadStorage<'a, TileSprite>,
+  elevations: ReadStorage<'a, Elevation>,
+  transitions: ReadStorage<'a, TileTransition>,
+  visibility: Read<'a, Visibility>,
+  light: Read<'a, AmbientLight>,
+  light_map: Read<'a, LightMap>,
   tex_assets: Read<'a, AssetStorage<Texture>>
 }
 
+const MAX_CORNERS: usize = 6;
+// tile props texture holds the sprite, the transition overlay with its edge mask,
+// then the tile state: fog of war shade, 0 hides the tile, explored ones are darkened,
+// and the color of point lights reaching the tile, added to the sky light
+// Tiles are laid out in rows of at most PROPS_WIDTH, every tile takes TEXELS_PER_TILE texels
+// one above another, so texel k of tile i is at (i % width, (i / width) * TEXELS_PER_TILE + k).
+const TEXELS_PER_TILE: usize = 3;
+const PROPS_WIDTH: usize = 1024;
+
+// Geometry shader emits a fan of `corner_count` corners around every tile center,
+// corners are in basis units, so one shader serves every grid topology.
+// Top face is lifted by elevation * elevation_step, side faces of the edges facing the viewer
+// are emitted down to the ground. There is no depth buffer, tiles are drawn back to front,
+// so faces behind a higher front neighbour are painted over by it.
 #[repr(C, align(16))]
 #[derive(Clone, Copy, Debug, Uniform)]
 struct TileMapArguments {
   tile_size: vec2,
   texture_size: vec2,
   basis: mat2,
+  corners: [vec2; MAX_CORNERS],
+  corner_count: int,
+  elevation_step: float,
+  texels_per_tile: int,
+  light_color: vec3,
+  light_intensity: float,
 }
 
 impl<'a> PassData<'a> for TileMapPass {
@@ -114,9 +149,12 @@ fn to_v2<T: 'static +std::fmt::Debug+Copy+PartialEq>(f: &Vector2<T>) -> [T; 2] {
 impl Pass for TileMapPass {
   fn compile(&mut self, effect: NewEffect<'_>) -> Result<Effect> {
     use std::mem;
+    let vert = load_shader(SHADER_PATHS[0], VERT_SHADER);
+    let geom = load_shader(SHADER_PATHS[1], GEOM_SHADER);
+    let frag = load_shader(SHADER_PATHS[2], FRAG_SHADER);
 
     effect 
-      .geom(VERT_SHADER, GEOM_SHADER, FRAG_SHADER)
+      .geom(&vert, &geom, &frag)
       .with_primitive_type(Primitive::PointList)
       .with_texture("tile_sheet")
       .with_texture("tile_props")
@@ -158,10 +196,21 @@ impl Pass for TileMapPass {
     for (tile_map, texture) in (&data.tile_map, &data.texture_info).join() {
       let basis = tile_map.get_basis();
       let basis: [[f32; 2]; 2] = basis.into();
+      let outline = tile_map.topology.corners();
+      let mut corners: [vec2; MAX_CORNERS] = [[0.0, 0.0].into(); MAX_CORNERS];
+      for (corner, c) in corners.iter_mut().zip(outline.iter()) {
+        *corner = to_v2(c).into();
+      }
       let tm_args = TileMapArguments {
         tile_size: to_v2(&tile_map.scale).into(),
         texture_size: to_v2(&g::vec2(texture.size.x as f32, texture.size.y as f32)).into(),
-        basis: basis.into()
+        basis: basis.into(),
+        corners,
+        corner_count: outline.len().min(MAX_CORNERS) as i32,
+        elevation_step: ELEVATION_STEP,
+        texels_per_tile: TEXELS_PER_TILE as i32,
+        light_color: data.light.color.into(),
+        light_intensity: data.light.intensity,
       };
       let texture = match data.tex_assets.get(&texture.texture) {
         None => continue,
@@ -174,17 +223,42 @@ impl Pass for TileMapPass {
       let mut hashier = DefaultHasher::new();
       let mut texture = Vec::<f32>::new();
       let mut buffer = Vec::<i32>::new();
-      for (tile, sprite_info) in (&data.tiles, &data.tile_sprites).join() {
+      let mut tiles: Vec<_> = (&data.tiles, &data.tile_sprites, (&data.elevations).maybe(), (&data.transitions).maybe())
+        .join()
+        .collect();
+      let depth = |t: &Tile| t.apply_basis(&tile_map.get_basis()).y;
+      tiles.sort_by(|a, b| depth(b.0).partial_cmp(&depth(a.0)).unwrap_or(std::cmp::Ordering::Equal));
+      for (tile, sprite_info, elevation, transition) in tiles {
+        let elevation = elevation.map(|e| e.0).unwrap_or(0);
         tile.hash(&mut hashier);
         sprite_info.hash(&mut hashier);
+        elevation.hash(&mut hashier);
+        transition.hash(&mut hashier);
         buffer.push(tile.position.x);
         buffer.push(tile.position.y);
         buffer.push(render.amount as i32);
+        buffer.push(elevation);
         
         texture.push(sprite_info.offset.x);
         texture.push(sprite_info.offset.y);
         texture.push(sprite_info.size.x);
         texture.push(sprite_info.size.y);
+        match transition {
+          None => texture.extend_from_slice(&[0.0, 0.0, 0.0, 0.0]),
+          Some(t) => {
+            texture.push(t.overlay.x);
+            texture.push(t.overlay.y);
+            texture.push(t.mask as f32);
+            texture.push(0.0);
+          }
+        }
+        let shade = fog_shade(data.visibility.of(PLAYER_FACTION, tile));
+        let local = data.light_map.at(tile);
+        shade.to_bits().hash(&mut hashier);
+        for c in local.iter() {
+          c.to_bits().hash(&mut hashier);
+        }
+        texture.extend_from_slice(&[shade, local[0], local[1], local[2]]);
 
         render.amount += 1;
       }
@@ -223,11 +297,12 @@ impl Pass for TileMapPass {
 
       use gfx::memory::Typed;
       effect.data.vertex_bufs.push(buffer.raw().clone());
+      // one point per tile
       effect.draw(&Slice {
         start: 0,
-        end: buffer.len() as u32,
+        end: self.last_render.amount as u32,
         base_vertex: 0,
-        instances: Some((self.last_render.amount as u32, 0)),
+        instances: None,
         buffer: Default::default()
       },
       encoder
@@ -236,21 +311,55 @@ impl Pass for TileMapPass {
   }
 }
 
+fn fog_shade(visibility: TileVisibility) -> f32 {
+  match visibility {
+    TileVisibility::Unexplored => 0.0,
+    TileVisibility::Explored => 0.5,
+    TileVisibility::Visible => 1.0,
+  }
+}
+
 fn prepare_texture(data: Vec<f32>, factory: &mut Factory) -> Option<(f32, LocalTexture)> {
-  opt(prepare_texture_res(data, factory))
+  use gfx::Factory;
+  let tiles = data.len() / (4 * TEXELS_PER_TILE);
+  if tiles == 0 {
+    return None;
+  }
+  let width = tiles.min(PROPS_WIDTH);
+  let height = (tiles + width - 1) / width * TEXELS_PER_TILE;
+  let max = factory.get_capabilities().max_texture_size;
+  if height > max {
+    println!("tile map of {} tiles needs a {}x{} tile props texture, more than {} is not supported", tiles, width, height, max);
+    return None;
+  }
+  let mut texels = vec![0.0; width * height * 4];
+  for i in 0..tiles {
+    for k in 0..TEXELS_PER_TILE {
+      let from = (i * TEXELS_PER_TILE + k) * 4;
+      let to = (((i / width) * TEXELS_PER_TILE + k) * width + i % width) * 4;
+      texels[to..to + 4].copy_from_slice(&data[from..from + 4]);
+    }
+  }
+  match prepare_texture_res(texels, width as u16, height as u16, factory) {
+    Ok(t) => Some(t),
+    Err(e) => {
+      println!("cannot create tile props texture: {}", e);
+      None
+    }
+  }
 }
-fn prepare_texture_res(data: Vec<f32>, factory: &mut Factory) -> Result<(f32, LocalTexture) > {
+
+fn prepare_texture_res(data: Vec<f32>, w: u16, h: u16, factory: &mut Factory) -> Result<(f32, LocalTexture) > {
   use gfx::{
     Factory,
     format::{ SurfaceType, ChannelType, Swizzle },
     memory::{ Bind, Usage, cast_slice },
-    texture::{ SamplerInfo, FilterMethod, WrapMode, Kind, Mipmap, Info, ResourceDesc }
+    texture::{ AaMode, SamplerInfo, FilterMethod, WrapMode, Kind, Mipmap, Info, ResourceDesc }
   };
-  let w = (data.len() / 4) as u16;
 
   let tex = factory.create_texture_raw(
     Info {
-      kind: Kind::D1(w),
+      kind: Kind::D2(w, h, AaMode::Single),
       levels: 1,
       format: SurfaceType::R32_G32_B32_A32,
       bind: Bind::SHADER_RESOURCE,
@@ -279,10 +388,3 @@ fn prepare_texture_res(data: Vec<f32>, factory: &mut Factory) -> Result<(f32, Lo
   }))
 }
 
-fn opt<T>(r: Result<T>)-> Option<T> {
-  match r {
-    Ok(t) => Some(t),
-    _ => None
-  }
-}
-
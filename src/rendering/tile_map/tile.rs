@@ -1,7 +1,9 @@
 use std::hash::{ Hash, Hasher };
 use amethyst::ecs::{Component, DenseVecStorage};
 use amethyst::core::nalgebra::{ Vector2, Vector3, Matrix2 };
+use serde_derive::{ Serialize, Deserialize };
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Tile {
   pub position: Vector3<i32>
 }
@@ -12,6 +14,14 @@ pub struct TileSprite {
 }
   
 
+// height of the tile in steps, 0 is the ground level
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Elevation(pub i32);
+
+impl Component for Elevation {
+  type Storage = DenseVecStorage<Self>;
+}
+
 impl Component for Tile {
   type Storage = DenseVecStorage<Self>;
 }
@@ -45,3 +55,59 @@ impl Tile {
     basis * p
   }
 }
+
+pub const HEX_DIRECTIONS: [(i32, i32, i32); 6] = [
+  (1, -1, 0),
+  (1, 0, -1),
+  (0, 1, -1),
+  (-1, 1, 0),
+  (-1, 0, 1),
+  (0, -1, 1),
+];
+
+impl Tile {
+  pub fn new(x: i32, y: i32) -> Self {
+    Tile {
+      position: Vector3::<i32>::new(x, y, -x - y)
+    }
+  }
+
+  pub fn neighbours(&self) -> Vec<Tile> {
+    HEX_DIRECTIONS.iter()
+      .map(|(x, y, _)| Tile::new(self.position.x + x, self.position.y + y))
+      .collect()
+  }
+
+  pub fn distance(&self, other: &Tile) -> i32 {
+    let d = self.position - other.position;
+    (d.x.abs() + d.y.abs() + d.z.abs()) / 2
+  }
+}
+
+impl Tile {
+  // tiles on the straight line to other, both ends included
+  pub fn line_to(&self, other: &Tile) -> Vec<Tile> {
+    let n = self.distance(other);
+    let a = self.position.map(|c| c as f32);
+    let b = other.position.map(|c| c as f32);
+    (0..=n)
+      .map(|i| {
+        let t = if n == 0 { 0.0 } else { i as f32 / n as f32 };
+        // nudge keeps rounding stable on edges between two tiles
+        let p = a + (b - a) * t + Vector3::<f32>::new(1e-6, 2e-6, -3e-6);
+        cube_round(p)
+      })
+      .collect()
+  }
+}
+
+pub fn cube_round(p: Vector3<f32>) -> Tile {
+  let mut r = p.map(|c| c.round());
+  let d = (r - p).map(|c| c.abs());
+  if d.x > d.y && d.x > d.z {
+    r.x = -r.y - r.z;
+  } else if d.y > d.z {
+    r.y = -r.x - r.z;
+  }
+  Tile::new(r.x as i32, r.y as i32)
+}
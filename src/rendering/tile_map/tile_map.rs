@@ -1,13 +1,11 @@
 use amethyst::ecs::{Component, DenseVecStorage};
-use amethyst::core::nalgebra::{ Vector2, Vector3, Matrix2 };
+use amethyst::core::nalgebra::{ Vector2, Matrix2 };
 use amethyst::renderer::{ TextureHandle };
 use super::tile::Tile;
+use super::topology::GridTopology;
 
-type Basis = (
-  Vector3<f32>, 
-  Vector3<f32>, 
-  Vector3<f32>
-);
+// world units a tile is lifted by per elevation step
+pub const ELEVATION_STEP: f32 = 6.0;
 
 pub struct TextureInfo {
   pub texture: TextureHandle,
@@ -15,35 +13,64 @@ pub struct TextureInfo {
 }
 
 pub struct TileMap {
-  pub basis: Basis,
+  pub topology: GridTopology,
+  pub basis: Matrix2<f32>,
   pub scale: Vector2<f32>
 }
 
 impl TileMap {
+  pub fn new(topology: GridTopology, scale: Vector2<f32>) -> Self {
+    TileMap {
+      topology,
+      basis: topology.basis(&scale),
+      scale
+    }
+  }
+
   pub fn get_basis(&self) -> Matrix2<f32> {
-    let (x, y, ..) = self.basis;
-    Matrix2::<f32>::new(
-      x.x, 
-      y.x, 
-      x.y,
-      y.y 
-    )
+    self.basis
   }
 
   pub fn calculate_tile(&self, v: &Vector2<f32>) -> Option<Tile> {
     self.get_basis()
       .try_inverse()
-      .map(|m| {
-        let v = m * v;
-        let v = Vector2::<i32>::new(
-          v.x.round() as i32,
-          v.y.round() as i32,
-        );
+      .map(|m| self.topology.round(&(m * v)))
+  }
 
-        Tile {
-          position: Vector3::<i32>::new(v.x, v.y, -v.x - v.y)
-        }
-      })
+  // center of the tile top, raised tiles are drawn higher on the screen
+  pub fn tile_center(&self, tile: &Tile, elevation: i32) -> Vector2<f32> {
+    tile.apply_basis(&self.get_basis()) + Vector2::<f32>::new(0.0, elevation as f32 * ELEVATION_STEP)
+  }
+
+  // Tile drawn under the point, whether by its top or by its side face.
+  // When several overlap, the one closer to the viewer (lower on the screen) wins.
+  pub fn calculate_raised_tile(
+    &self,
+    v: &Vector2<f32>,
+    levels: (i32, i32),
+    elevation_of: &Fn(&Tile) -> Option<i32>
+  ) -> Option<Tile> {
+    let (lowest, highest) = levels;
+    let mut best: Option<(f32, Tile)> = None;
+    for level in (lowest..=highest).rev() {
+      let lowered = v - Vector2::<f32>::new(0.0, level as f32 * ELEVATION_STEP);
+      let tile = match self.calculate_tile(&lowered) {
+        None => continue,
+        Some(t) => t
+      };
+      let elevation = match elevation_of(&tile) {
+        None => continue,
+        Some(e) => e
+      };
+      if elevation < level {
+        continue;
+      }
+      let depth = tile.apply_basis(&self.get_basis()).y;
+      if best.as_ref().map(|(d, _)| depth < *d).unwrap_or(true) {
+        best = Some((depth, tile));
+      }
+    }
+    best.map(|(_, t)| t).or_else(|| self.calculate_tile(v))
   }
 }
 
@@ -54,17 +81,3 @@ impl Component for TileMap {
 impl Component for TextureInfo {
   type Storage = DenseVecStorage<Self>;
 }
-
-pub fn hex_basis(scale: Vector2<f32>) -> Basis {
-  use std::f32::consts::{ PI };
-  let angle_axis = PI / 6.0; 
-  let angle_hex = PI / 3.0; 
-  let k = 2.0 * angle_hex.sin();
-  (
-    Vector3::<f32>::new(
-      angle_axis.cos() * k * scale.x, 
-      angle_axis.sin() * k * scale.y, 0.0) * 1.0, 
-    Vector3::<f32>::new(0.0, 1.0, 0.0) * k * scale.y,
-    Vector3::<f32>::new(0.0, 0.0, 1.0)
-  )
-}
@@ -0,0 +1,199 @@
+use amethyst::core::nalgebra::{ Vector2, Vector3, Matrix2 };
+use serde_derive::{ Serialize, Deserialize };
+use super::tile::{ Tile, cube_round };
+
+// hex grids use cube coordinates, square ones keep column and row in x and y
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GridTopology {
+  HexPointy,
+  HexFlat,
+  Square4,
+  Square8,
+  IsoDiamond,
+}
+
+impl Default for GridTopology {
+  fn default() -> Self {
+    GridTopology::HexFlat
+  }
+}
+
+const SQUARE_SIDES: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const SQUARE_DIAGONALS: [(i32, i32); 4] = [(1, 1), (-1, 1), (-1, -1), (1, -1)];
+// corners of a hexagon around its center, in axial coordinates
+const HEX_CORNERS: [(f32, f32); 6] = [
+  (2.0 / 3.0, -1.0 / 3.0),
+  (1.0 / 3.0, 1.0 / 3.0),
+  (-1.0 / 3.0, 2.0 / 3.0),
+  (-2.0 / 3.0, 1.0 / 3.0),
+  (-1.0 / 3.0, -1.0 / 3.0),
+  (1.0 / 3.0, -2.0 / 3.0),
+];
+const SQUARE_CORNERS: [(f32, f32); 4] = [(0.5, -0.5), (0.5, 0.5), (-0.5, 0.5), (-0.5, -0.5)];
+
+impl GridTopology {
+  pub fn is_hex(&self) -> bool {
+    match self {
+      GridTopology::HexPointy | GridTopology::HexFlat => true,
+      _ => false,
+    }
+  }
+
+  // columns of the matrix are world offsets of the x and y steps
+  pub fn basis(&self, scale: &Vector2<f32>) -> Matrix2<f32> {
+    use std::f32::consts::PI;
+    let k = 2.0 * (PI / 3.0).sin();
+    let (x, y) = match self {
+      GridTopology::HexFlat => (
+        Vector2::<f32>::new((PI / 6.0).cos() * k * scale.x, (PI / 6.0).sin() * k * scale.y),
+        Vector2::<f32>::new(0.0, k * scale.y),
+      ),
+      GridTopology::HexPointy => (
+        Vector2::<f32>::new(k * scale.x, 0.0),
+        Vector2::<f32>::new(k * scale.x / 2.0, 1.5 * scale.y),
+      ),
+      GridTopology::Square4 | GridTopology::Square8 => (
+        Vector2::<f32>::new(2.0 * scale.x, 0.0),
+        Vector2::<f32>::new(0.0, 2.0 * scale.y),
+      ),
+      GridTopology::IsoDiamond => (
+        Vector2::<f32>::new(scale.x, scale.y),
+        Vector2::<f32>::new(-scale.x, scale.y),
+      ),
+    };
+    Matrix2::<f32>::new(x.x, y.x, x.y, y.y)
+  }
+
+  // grid coordinates given in basis units, rounded to the tile containing them
+  pub fn round(&self, v: &Vector2<f32>) -> Tile {
+    if self.is_hex() {
+      cube_round(Vector3::<f32>::new(v.x, v.y, -v.x - v.y))
+    } else {
+      Tile::new(v.x.round() as i32, v.y.round() as i32)
+    }
+  }
+
+  pub fn neighbours(&self, tile: &Tile) -> Vec<Tile> {
+    let p = tile.position;
+    match self {
+      GridTopology::HexPointy | GridTopology::HexFlat => tile.neighbours(),
+      GridTopology::Square4 | GridTopology::IsoDiamond => SQUARE_SIDES.iter()
+        .map(|(x, y)| Tile::new(p.x + x, p.y + y))
+        .collect(),
+      GridTopology::Square8 => SQUARE_SIDES.iter()
+        .chain(SQUARE_DIAGONALS.iter())
+        .map(|(x, y)| Tile::new(p.x + x, p.y + y))
+        .collect(),
+    }
+  }
+
+  // number of steps between tiles
+  pub fn distance(&self, a: &Tile, b: &Tile) -> i32 {
+    let d = a.position - b.position;
+    match self {
+      GridTopology::HexPointy | GridTopology::HexFlat => a.distance(b),
+      GridTopology::Square4 | GridTopology::IsoDiamond => d.x.abs() + d.y.abs(),
+      GridTopology::Square8 => d.x.abs().max(d.y.abs()),
+    }
+  }
+
+  // tiles on the straight line between a and b, both ends included
+  pub fn line(&self, a: &Tile, b: &Tile) -> Vec<Tile> {
+    if self.is_hex() {
+      return a.line_to(b);
+    }
+    let d = b.position - a.position;
+    let n = d.x.abs().max(d.y.abs());
+    let from = Vector2::<f32>::new(a.position.x as f32, a.position.y as f32);
+    let delta = Vector2::<f32>::new(d.x as f32, d.y as f32);
+    let mut line: Vec<Tile> = Vec::new();
+    for i in 0..=n {
+      let t = if n == 0 { 0.0 } else { i as f32 / n as f32 };
+      let p = from + delta * t + Vector2::<f32>::new(1e-6, 2e-6);
+      let tile = self.round(&p);
+      // four neighbour grids can not step diagonally, corner tile is put in between
+      if let (GridTopology::Square4, Some(last)) | (GridTopology::IsoDiamond, Some(last)) = (self, line.last()) {
+        let step = tile.position - last.position;
+        if step.x != 0 && step.y != 0 {
+          line.push(Tile::new(tile.position.x, last.position.y));
+        }
+      }
+      line.push(tile);
+    }
+    line
+  }
+
+  // every tile within radius steps from center, including the center
+  pub fn area(&self, center: &Tile, radius: i32) -> Vec<Tile> {
+    let c = center.position;
+    let mut result = Vec::new();
+    for x in -radius..=radius {
+      for y in -radius..=radius {
+        let tile = Tile::new(c.x + x, c.y + y);
+        if self.distance(center, &tile) <= radius {
+          result.push(tile);
+        }
+      }
+    }
+    result
+  }
+
+  // neighbours across the edge from corner i to corner i + 1, in the order of corners
+  pub fn edge_neighbours(&self, tile: &Tile) -> Vec<Tile> {
+    let corners = self.corners();
+    let p = tile.position;
+    (0..corners.len())
+      .map(|i| corners[i] + corners[(i + 1) % corners.len()])
+      .map(|d| Tile::new(p.x + d.x.round() as i32, p.y + d.y.round() as i32))
+      .collect()
+  }
+
+  // outline of a tile relative to its center, in basis units, for the geometry shader
+  pub fn corners(&self) -> Vec<Vector2<f32>> {
+    let corners: &[(f32, f32)] = if self.is_hex() { &HEX_CORNERS } else { &SQUARE_CORNERS };
+    corners.iter().map(|(x, y)| Vector2::<f32>::new(*x, *y)).collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // relative to the tile at 2, 3
+  fn offsets(tiles: &[Tile]) -> Vec<(i32, i32)> {
+    tiles.iter().map(|t| (t.position.x - 2, t.position.y - 3)).collect()
+  }
+
+  #[test]
+  fn square_edges_follow_the_corners() {
+    let tile = Tile::new(2, 3);
+    let expected = vec![(1, 0), (0, 1), (-1, 0), (0, -1)];
+    for topology in [GridTopology::Square4, GridTopology::Square8, GridTopology::IsoDiamond].iter() {
+      assert_eq!(offsets(&topology.edge_neighbours(&tile)), expected);
+    }
+  }
+
+  #[test]
+  fn hex_edges_follow_the_corners() {
+    let tile = Tile::new(2, 3);
+    let topology = GridTopology::HexFlat;
+    assert_eq!(
+      offsets(&topology.edge_neighbours(&tile)),
+      vec![(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)]
+    );
+  }
+
+  #[test]
+  fn edge_neighbours_are_neighbours() {
+    let tile = Tile::new(-1, 4);
+    for topology in [GridTopology::HexFlat, GridTopology::HexPointy, GridTopology::Square4, GridTopology::Square8, GridTopology::IsoDiamond].iter() {
+      let edges = topology.edge_neighbours(&tile);
+      assert_eq!(edges.len(), topology.corners().len());
+      let neighbours = topology.neighbours(&tile);
+      for e in edges.iter() {
+        assert!(neighbours.iter().any(|n| n.position == e.position), "{:?} {:?}", topology, e.position);
+        assert_eq!(topology.distance(&tile, e), 1);
+      }
+    }
+  }
+}
@@ -0,0 +1,148 @@
+use amethyst::{
+  core::{ Hidden, Time, nalgebra::Vector2 },
+  ecs::{ Component, DenseVecStorage, Entities, Join, Read, ReadStorage, System, WriteStorage },
+  prelude::{ Builder, World },
+  renderer::{ ScreenDimensions, TextureHandle },
+};
+use nalgebra_glm::translation2d;
+use crate::asset_loader::load_png_texture;
+use crate::random::Rng;
+use crate::rendering::screen_space::screen_rect::{ ScreenRect, Transform2D };
+use crate::simulation::{ Weather, WeatherState };
+
+const PARTICLES: usize = 160;
+// under the ui buttons
+const OVERLAY_LAYER: u8 = 1;
+
+pub struct WeatherParticle {
+  // fall speed multiplier, so particles do not move in lockstep
+  weight: f32,
+}
+
+impl Component for WeatherParticle {
+  type Storage = DenseVecStorage<Self>;
+}
+
+// Screen space particles drawn over the map. Purely cosmetic,
+// they use their own random numbers and never touch the simulation.
+pub struct WeatherOverlay {
+  rain: TextureHandle,
+  snow: TextureHandle,
+}
+
+// particles shown, velocity in pixels per second, size of a particle
+fn appearance(weather: Weather) -> (usize, Vector2<f32>, Vector2<f32>) {
+  match weather {
+    Weather::Clear => (0, Vector2::new(0.0, 0.0), Vector2::new(0.0, 0.0)),
+    Weather::Rain => (100, Vector2::new(-60.0, -700.0), Vector2::new(2.0, 14.0)),
+    Weather::Snow => (120, Vector2::new(-20.0, -80.0), Vector2::new(5.0, 5.0)),
+    Weather::Storm => (PARTICLES, Vector2::new(-300.0, -900.0), Vector2::new(2.0, 18.0)),
+  }
+}
+
+pub fn create_weather_overlay(world: &mut World) {
+  world.register::<ScreenRect>();
+  world.register::<Transform2D>();
+  world.register::<WeatherParticle>();
+  world.register::<Hidden>();
+  let overlay = WeatherOverlay {
+    rain: load_png_texture(world, "./resources/sprites/weather/rain.png".to_string()),
+    snow: load_png_texture(world, "./resources/sprites/weather/snow.png".to_string()),
+  };
+  let mut rng = Rng::new(PARTICLES as u64);
+  for _ in 0..PARTICLES {
+    let mut rect: ScreenRect = Default::default();
+    rect.layer = OVERLAY_LAYER;
+    world.create_entity()
+      .with(rect)
+      .with(overlay.rain.clone())
+      .with(Transform2D::default())
+      .with(WeatherParticle { weight: 0.7 + rng.next_f32() * 0.6 })
+      .with(Hidden)
+      .build();
+  }
+  world.add_resource(overlay);
+}
+
+pub struct WeatherOverlaySystem {
+  rng: Rng,
+  positions: Vec<Vector2<f32>>,
+}
+
+impl WeatherOverlaySystem {
+  pub fn new() -> Self {
+    WeatherOverlaySystem {
+      rng: Rng::new(1),
+      positions: Vec::new(),
+    }
+  }
+
+  fn random_position(&mut self, screen: &Vector2<f32>) -> Vector2<f32> {
+    Vector2::new(self.rng.next_f32() * screen.x * 1.5, self.rng.next_f32() * screen.y)
+  }
+}
+
+impl<'a> System<'a> for WeatherOverlaySystem {
+  type SystemData = (
+    Read<'a, WeatherState>,
+    Read<'a, Time>,
+    Option<Read<'a, ScreenDimensions>>,
+    Option<Read<'a, WeatherOverlay>>,
+    Entities<'a>,
+    ReadStorage<'a, WeatherParticle>,
+    WriteStorage<'a, ScreenRect>,
+    WriteStorage<'a, Transform2D>,
+    WriteStorage<'a, TextureHandle>,
+    WriteStorage<'a, Hidden>,
+  );
+
+  fn run(&mut self, (
+    weather,
+    time,
+    screen_dim,
+    overlay,
+    entities,
+    particles,
+    mut rects,
+    mut transforms,
+    mut textures,
+    mut hidden
+  ): Self::SystemData) {
+    let (screen, overlay) = match (screen_dim, overlay) {
+      (Some(sd), Some(o)) => (Vector2::<f32>::new(sd.width(), sd.height()), o),
+      _ => return
+    };
+    let (shown, velocity, size) = appearance(weather.current);
+    let texture = if weather.current == Weather::Snow { &overlay.snow } else { &overlay.rain };
+    let dt = time.delta_seconds();
+    let joined = (&entities, &particles, &mut rects, &mut transforms, &mut textures).join().enumerate();
+    for (i, (e, particle, rect, transform, t)) in joined {
+      if i >= shown {
+        if !hidden.contains(e) {
+          if let Err(err) = hidden.insert(e, Hidden) {
+            println!("cannot hide weather particle: {}", err);
+          }
+        }
+        continue;
+      }
+      if hidden.contains(e) {
+        hidden.remove(e);
+      }
+      while self.positions.len() <= i {
+        let p = self.random_position(&screen);
+        self.positions.push(p);
+      }
+      let mut p = self.positions[i] + velocity * particle.weight * dt;
+      // particles leaving the screen come back from the top, spread wider to cover the wind
+      if p.y < 0.0 || p.x < 0.0 {
+        p = Vector2::new(self.rng.next_f32() * screen.x * 1.5, screen.y);
+      }
+      self.positions[i] = p;
+      rect.size = size;
+      transform.model = translation2d(&p);
+      if *t != *texture {
+        *t = texture.clone();
+      }
+    }
+  }
+}
@@ -0,0 +1,169 @@
+use std::fs::{ self, File };
+use std::path::Path;
+use std::io::{ Read, Write };
+use std::collections::VecDeque;
+use serde_derive::{ Serialize, Deserialize };
+use crate::game_messages::GameMessage;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RecordedFrame {
+  pub tick: u64,
+  pub messages: Vec<GameMessage>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Recording {
+  pub seed: u64,
+  #[serde(default)]
+  pub scenario: Option<String>,
+  pub frames: Vec<RecordedFrame>,
+  // tick the session was saved at, the world is restored up to it
+  #[serde(default)]
+  pub end_tick: u64,
+}
+
+enum Mode {
+  Idle,
+  Recording(String, Recording),
+  // frames left to apply, tick to replay up to, and the recording resumed sessions go on with
+  Replaying(VecDeque<RecordedFrame>, u64, Option<(String, Recording)>),
+}
+
+pub struct Replay {
+  mode: Mode,
+}
+
+impl Default for Replay {
+  fn default() -> Self {
+    Replay {
+      mode: Mode::Idle,
+    }
+  }
+}
+
+impl Replay {
+  pub fn record(path: String, seed: u64, scenario: Option<String>) -> Self {
+    Replay {
+      mode: Mode::Recording(path, Recording {
+        seed,
+        scenario,
+        frames: Vec::new(),
+        end_tick: 0,
+      }),
+    }
+  }
+
+  fn read(path: &str) -> Result<Recording, String> {
+    let mut content = String::new();
+    File::open(path)
+      .and_then(|mut f| f.read_to_string(&mut content))
+      .map_err(|e| format!("cannot read replay {}: {}", path, e))?;
+    ron::de::from_str(&content)
+      .map_err(|e| format!("cannot parse replay {}: {}", path, e))
+  }
+
+  // returns replay along with seed and scenario it was recorded with
+  pub fn load(path: &str) -> Result<(Self, u64, Option<String>), String> {
+    let recording = Replay::read(path)?;
+    let (seed, scenario) = (recording.seed, recording.scenario);
+    let replay = Replay {
+      mode: Mode::Replaying(recording.frames.into_iter().collect(), recording.end_tick, None),
+    };
+    Ok((replay, seed, scenario))
+  }
+
+  // Same as load, but once the saved tick is reached the session is recorded on,
+  // new input is appended to the loaded frames and saved back to the same file.
+  pub fn resume(path: &str) -> Result<(Self, u64, Option<String>), String> {
+    let recording = Replay::read(path)?;
+    let (seed, scenario) = (recording.seed, recording.scenario.clone());
+    let frames = recording.frames.iter().cloned().collect();
+    let replay = Replay {
+      mode: Mode::Replaying(frames, recording.end_tick, Some((path.to_string(), recording))),
+    };
+    Ok((replay, seed, scenario))
+  }
+
+  pub fn is_idle(&self) -> bool {
+    match self.mode {
+      Mode::Idle => true,
+      _ => false
+    }
+  }
+
+  pub fn is_replaying(&self) -> bool {
+    match self.mode {
+      Mode::Replaying(_, _, _) => true,
+      _ => false
+    }
+  }
+
+  // ticks a resumed session still has to run through before it is handed back to the player
+  pub fn catch_up(&self, tick: u64) -> Option<u64> {
+    match &self.mode {
+      Mode::Replaying(_, end_tick, Some(_)) if *end_tick > tick => Some(end_tick - tick),
+      _ => None
+    }
+  }
+
+  // Takes messages produced live before simulation tick and returns the ones which must be applied.
+  // While replaying live messages are dropped and recorded ones are fed instead.
+  pub fn process(&mut self, tick: u64, live: Vec<GameMessage>) -> Vec<GameMessage> {
+    let result = match &mut self.mode {
+      Mode::Idle => live,
+      Mode::Recording(_, recording) => {
+        if !live.is_empty() {
+          recording.frames.push(RecordedFrame {
+            tick,
+            messages: live.clone()
+          });
+        }
+        live
+      }
+      Mode::Replaying(frames, _, _) => {
+        let mut result = Vec::new();
+        while frames.front().map(|f| f.tick <= tick).unwrap_or(false) {
+          if let Some(mut f) = frames.pop_front() {
+            result.append(&mut f.messages);
+          }
+        }
+        result
+      }
+    };
+    // once recorded session is over, the game goes on with live input
+    let finished = match &self.mode {
+      Mode::Replaying(frames, end_tick, _) => frames.is_empty() && tick >= *end_tick,
+      _ => false
+    };
+    if finished {
+      let mode = std::mem::replace(&mut self.mode, Mode::Idle);
+      if let Mode::Replaying(_, _, Some((path, recording))) = mode {
+        self.mode = Mode::Recording(path, recording);
+      }
+    }
+    result
+  }
+
+  pub fn save(&mut self, end_tick: u64) {
+    if let Mode::Recording(path, recording) = &mut self.mode {
+      recording.end_tick = end_tick;
+      if let Some(dir) = Path::new(path).parent() {
+        if let Err(e) = fs::create_dir_all(dir) {
+          println!("cannot create directory for replay {}: {}", path, e);
+          return;
+        }
+      }
+      let content = match ron::ser::to_string_pretty(recording, Default::default()) {
+        Ok(c) => c,
+        Err(e) => {
+          println!("cannot serialize replay: {}", e);
+          return;
+        }
+      };
+      match File::create(path).and_then(|mut f| f.write_all(content.as_bytes())) {
+        Ok(_) => println!("replay is saved to {}", path),
+        Err(e) => println!("cannot save replay to {}: {}", path, e)
+      }
+    }
+  }
+}
@@ -2,6 +2,8 @@ mod resource;
 
 use amethyst::{
   ecs:: {
+    Component,
+    DenseVecStorage,
     World,
     Builder
   },
@@ -12,9 +14,62 @@ pub use self::resource::{
   Resource,
   Stacking,
   ResourceInfo,
+  ResourceDictionary,
+  DICTIONARY_PATH,
+  create_resource_dictionary,
 };
 
+#[derive(Clone, Copy, Debug)]
+pub struct ResourceStack {
+  pub resource: Resource,
+  pub count: u32,
+}
+
+impl Component for ResourceStack {
+  type Storage = DenseVecStorage<Self>;
+}
+
+// things carried by a pawn
+#[derive(Default, Clone, Debug)]
+pub struct Inventory {
+  pub items: Vec<ResourceStack>,
+}
+
+impl Inventory {
+  pub fn add(&mut self, resource: Resource, count: u32) {
+    match self.items.iter_mut().find(|s| s.resource == resource) {
+      Some(stack) => stack.count += count,
+      None => self.items.push(ResourceStack { resource, count })
+    }
+  }
+
+  // returns how many were actually taken
+  pub fn take(&mut self, resource: Resource, count: u32) -> u32 {
+    let taken = match self.items.iter_mut().find(|s| s.resource == resource) {
+      None => 0,
+      Some(stack) => {
+        let taken = stack.count.min(count);
+        stack.count -= taken;
+        taken
+      }
+    };
+    self.items.retain(|s| s.count > 0);
+    taken
+  }
+}
+
+impl Component for Inventory {
+  type Storage = DenseVecStorage<Self>;
+}
 
-pub fn place_resource(world: &mut World, res: Resource, tile: Tile) {
-  
+pub fn place_resource(world: &mut World, res: Resource, count: u32, tile: Tile) {
+  world.register::<ResourceStack>();
+  world.register::<Tile>();
+  world.create_entity()
+    .with(ResourceStack {
+      resource: res,
+      count
+    })
+    .with(tile)
+    .build();
 }
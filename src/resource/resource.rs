@@ -1,19 +1,25 @@
 use std::collections::hash_map::HashMap;
-#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+use std::fs;
+use std::path::Path;
+use serde_derive::{ Serialize, Deserialize };
+
+pub const DICTIONARY_PATH: &str = "./resources/data/resources.ron";
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum Resource {
   PieceOfMeat,
   Apple,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Stacking {
-  unordered: u32,
-  ordered: u32,
+  pub unordered: u32,
+  pub ordered: u32,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct ResourceInfo {
-  stacking: Stacking,
+  pub stacking: Stacking,
 }
 
 pub fn create_resource_dictionary() -> HashMap<Resource, ResourceInfo> {
@@ -32,3 +38,33 @@ pub fn create_resource_dictionary() -> HashMap<Resource, ResourceInfo> {
   });
   map
 }
+
+pub struct ResourceDictionary {
+  pub info: HashMap<Resource, ResourceInfo>,
+}
+
+impl Default for ResourceDictionary {
+  fn default() -> Self {
+    ResourceDictionary::load(DICTIONARY_PATH).unwrap_or_else(|e| {
+      println!("{}, built-in dictionary is used", e);
+      ResourceDictionary {
+        info: create_resource_dictionary()
+      }
+    })
+  }
+}
+
+impl ResourceDictionary {
+  pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+      .map_err(|e| format!("cannot read resource dictionary {}: {}", path.display(), e))?;
+    let info = ron::de::from_str(&content)
+      .map_err(|e| format!("cannot parse resource dictionary {}: {}", path.display(), e))?;
+    Ok(ResourceDictionary { info })
+  }
+
+  pub fn get(&self, resource: &Resource) -> Option<&ResourceInfo> {
+    self.info.get(resource)
+  }
+}
@@ -0,0 +1,400 @@
+use std::fs;
+use std::path::Path;
+use amethyst::{
+  core::nalgebra::{ Vector3 },
+  ecs::{ Entity, Join, World },
+};
+use serde_derive::{ Serialize, Deserialize };
+use crate::game_state::TileMapTilesKind;
+use crate::pawn::{ Spece, Sex, PawnId, place_debug_pawn, place_pawn };
+use crate::random::{ GameSeed, Rng };
+use crate::rendering::tile_map::{
+  Tile,
+  TileMap,
+  Elevation,
+  GridTopology,
+  create_tile_map,
+  create_empty_tile_map,
+  create_tile,
+  tile_center,
+  attach_tile_map_texture,
+};
+use crate::resource::{ Resource, place_resource };
+use crate::simulation::{ Clock, Fire, WeatherState, PlantKind, GrowthStage, Pack, TERRITORY_RADIUS, place_fire, place_plant };
+
+pub const SCENARIO_DIR: &str = "./resources/scenarios";
+pub const MAP_DIR: &str = "./resources/maps";
+// no dens this close to the map center, where the colony starts
+const SAFE_RADIUS: i32 = 6;
+const MAX_PACK: i32 = 4;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapSettings {
+  pub topology: GridTopology,
+  pub radius: u16,
+  pub texture: String,
+  pub lakes: u32,
+  pub lake_radius: i32,
+  // chance of a land tile to be dirt instead of grass
+  pub dirt: f32,
+  // multiplier of the plant chances of every biome
+  pub plants: f32,
+  // multiplier of the wolf den chances of every biome
+  pub wildlife: f32,
+  // map saved by the editor, generation settings above are ignored when it is given
+  pub file: Option<String>,
+}
+
+impl Default for MapSettings {
+  fn default() -> Self {
+    MapSettings {
+      topology: GridTopology::default(),
+      radius: 4,
+      texture: "./resources/sprites/terrain/tiles.png".to_string(),
+      lakes: 0,
+      lake_radius: 1,
+      dirt: 0.0,
+      plants: 1.0,
+      wildlife: 1.0,
+      file: None,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MapTile {
+  pub at: (i32, i32),
+  pub kind: TileMapTilesKind,
+  #[serde(default)]
+  pub elevation: i32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MapFile {
+  #[serde(default)]
+  pub topology: GridTopology,
+  pub tiles: Vec<MapTile>,
+}
+
+impl MapFile {
+  pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+      .map_err(|e| format!("cannot read map {}: {}", path.display(), e))?;
+    ron::de::from_str(&content)
+      .map_err(|e| format!("cannot parse map {}: {}", path.display(), e))
+  }
+
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() {
+      fs::create_dir_all(dir)
+        .map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+    }
+    let content = ron::ser::to_string_pretty(self, Default::default())
+      .map_err(|e| format!("cannot serialize map: {}", e))?;
+    fs::write(path, content)
+      .map_err(|e| format!("cannot write map {}: {}", path.display(), e))
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PawnSpawn {
+  pub spece: Spece,
+  pub at: (i32, i32),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResourceSpawn {
+  pub resource: Resource,
+  pub count: u32,
+  pub at: (i32, i32),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FireSpawn {
+  pub fire: Fire,
+  pub at: (i32, i32),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scenario {
+  pub name: String,
+  pub map: MapSettings,
+  pub pawns: Vec<PawnSpawn>,
+  pub resources: Vec<ResourceSpawn>,
+  pub fires: Vec<FireSpawn>,
+  pub start: Clock,
+}
+
+impl Default for Scenario {
+  fn default() -> Self {
+    Scenario {
+      name: "debug".to_string(),
+      map: MapSettings::default(),
+      pawns: Vec::new(),
+      resources: Vec::new(),
+      fires: Vec::new(),
+      start: Clock::default(),
+    }
+  }
+}
+
+// path of the scenario file, None stands for the built-in debug one
+#[derive(Default, Clone)]
+pub struct SelectedScenario(pub Option<String>);
+
+impl Scenario {
+  pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+      .map_err(|e| format!("cannot read scenario {}: {}", path.display(), e))?;
+    ron::de::from_str(&content)
+      .map_err(|e| format!("cannot parse scenario {}: {}", path.display(), e))
+  }
+
+  pub fn from_selection(selected: &SelectedScenario) -> Self {
+    match &selected.0 {
+      None => Scenario::default(),
+      Some(path) => Scenario::load(path).unwrap_or_else(|e| {
+        println!("{}, debug scenario is used", e);
+        Scenario::default()
+      })
+    }
+  }
+}
+
+pub fn available_scenarios() -> Vec<String> {
+  let mut result: Vec<String> = fs::read_dir(SCENARIO_DIR)
+    .map(|dir| dir
+      .filter_map(|e| e.ok())
+      .map(|e| e.path())
+      .filter(|p| p.extension().map(|e| e == "ron").unwrap_or(false))
+      .map(|p| p.to_string_lossy().into_owned())
+      .collect())
+    .unwrap_or_default();
+  result.sort();
+  result
+}
+
+// Builds the map and puts starting pawns, resources and fires. Texture is skipped in headless mode.
+pub fn apply_scenario(world: &mut World, scenario: &Scenario, textured: bool) -> Entity {
+  let seed = *world.res.entry::<GameSeed>().or_insert_with(GameSeed::default);
+  let tile_map = build_map(world, &scenario.map, &seed, textured);
+  world.add_resource(scenario.start);
+  // saved sessions are replayed from the start, so weather always begins from the seed
+  world.add_resource(WeatherState::new(&seed));
+
+  for pawn in scenario.pawns.iter() {
+    let tile = match spawn_tile(world, pawn.at, &format!("{:?}", pawn.spece)) {
+      None => continue,
+      Some(t) => t
+    };
+    let at = match tile_center(world, &tile) {
+      None => return tile_map,
+      Some(at) => at
+    };
+    place_debug_pawn(world, pawn.spece, at, tile);
+  }
+  for stack in scenario.resources.iter() {
+    if let Some(tile) = spawn_tile(world, stack.at, &format!("{:?}", stack.resource)) {
+      place_resource(world, stack.resource, stack.count, tile);
+    }
+  }
+  for fire in scenario.fires.iter() {
+    if let Some(tile) = spawn_tile(world, fire.at, &format!("{:?}", fire.fire)) {
+      place_fire(world, fire.fire, tile);
+    }
+  }
+  spawn_wildlife(world, tile_map, scenario.map.wildlife, &seed);
+  tile_map
+}
+
+// spawns must stand on land of the map, the others are reported and skipped
+fn spawn_tile(world: &World, at: (i32, i32), what: &str) -> Option<Tile> {
+  let tile = Tile::new(at.0, at.1);
+  let kind = {
+    let tiles = world.read_storage::<Tile>();
+    let kinds = world.read_storage::<TileMapTilesKind>();
+    let found = (&tiles, &kinds).join()
+      .find(|(t, _)| t.position == tile.position)
+      .map(|(_, k)| *k);
+    found
+  };
+  match kind {
+    None => {
+      println!("scenario spawn of {} at {:?} is off the map, skipped", what, at);
+      None
+    }
+    Some(TileMapTilesKind::Water) => {
+      println!("scenario spawn of {} at {:?} is on water, skipped", what, at);
+      None
+    }
+    Some(_) => Some(tile)
+  }
+}
+
+pub fn build_map(world: &mut World, settings: &MapSettings, seed: &GameSeed, textured: bool) -> Entity {
+  let from_file = settings.file.as_ref().and_then(|path| MapFile::load(path).map_err(|e| {
+    println!("{}, map is generated instead", e);
+  }).ok());
+  let tile_map = match from_file {
+    Some(file) => {
+      let tile_map = create_empty_tile_map(world, file.topology);
+      for t in file.tiles.iter() {
+        let position = Vector3::<i32>::new(t.at.0, t.at.1, -t.at.0 - t.at.1);
+        create_tile(world, position, t.kind, Elevation(t.elevation));
+      }
+      // map files store only the ground, plants are scattered the same way as on generated maps
+      scatter_plants(world, settings.plants, &mut Rng::from_seed(seed, 2));
+      tile_map
+    }
+    None => generate_map(world, settings, seed)
+  };
+  if textured {
+    attach_tile_map_texture(world, tile_map, settings.texture.clone());
+  }
+  tile_map
+}
+
+fn generate_map(world: &mut World, settings: &MapSettings, seed: &GameSeed) -> Entity {
+  let mut rng = Rng::from_seed(seed, 2);
+  let radius = settings.radius as i32 - 1;
+  let lakes: Vec<Tile> = (0..settings.lakes)
+    .map(|_| Tile::new(rng.range(-radius, radius + 1), rng.range(-radius, radius + 1)))
+    .collect();
+  let lake_radius = settings.lake_radius;
+  let dirt = settings.dirt;
+  let topology = settings.topology;
+  let tile_map = create_tile_map(world, topology, settings.radius, &mut |position: &Vector3<i32>| {
+    let tile = Tile::new(position.x, position.y);
+    if lakes.iter().any(|l| topology.distance(l, &tile) < lake_radius) {
+      TileMapTilesKind::Water
+    } else if rng.chance(dirt) {
+      TileMapTilesKind::Dirt
+    } else {
+      TileMapTilesKind::Grass
+    }
+  });
+  scatter_plants(world, settings.plants, &mut rng);
+  tile_map
+}
+
+// chances of a tile of the biome to get the plant
+fn plant_chances(kind: TileMapTilesKind) -> &'static [(PlantKind, f32)] {
+  match kind {
+    TileMapTilesKind::Grass => &[(PlantKind::AppleTree, 0.03), (PlantKind::BerryBush, 0.05)],
+    TileMapTilesKind::Dirt => &[(PlantKind::BerryBush, 0.03)],
+    TileMapTilesKind::Water | TileMapTilesKind::Ice => &[],
+  }
+}
+
+fn scatter_plants(world: &mut World, density: f32, rng: &mut Rng) {
+  let mut tiles: Vec<(Tile, TileMapTilesKind)> = {
+    let tiles = world.read_storage::<Tile>();
+    let kinds = world.read_storage::<TileMapTilesKind>();
+    let found = (&tiles, &kinds).join().map(|(t, k)| (t.clone(), *k)).collect();
+    found
+  };
+  tiles.sort_by_key(|(t, _)| (t.position.x, t.position.y));
+  for (tile, kind) in tiles {
+    let plant = plant_chances(kind).iter()
+      .find(|(_, chance)| rng.chance(chance * density))
+      .map(|(plant, _)| *plant);
+    if let Some(plant) = plant {
+      place_plant(world, plant, GrowthStage::Mature, tile);
+    }
+  }
+}
+
+// chance of a tile of the biome to hold a wolf den
+fn den_chance(kind: TileMapTilesKind) -> f32 {
+  match kind {
+    TileMapTilesKind::Grass => 0.02,
+    TileMapTilesKind::Dirt => 0.01,
+    TileMapTilesKind::Water | TileMapTilesKind::Ice => 0.0,
+  }
+}
+
+// dens away from the colony, far enough apart that territories do not overlap, tiles are sorted
+fn pick_dens(topology: GridTopology, tiles: &[(Tile, TileMapTilesKind)], density: f32, rng: &mut Rng) -> Vec<Tile> {
+  let center = Tile::new(0, 0);
+  let mut dens: Vec<Tile> = Vec::new();
+  for (tile, kind) in tiles.iter() {
+    if topology.distance(&center, tile) < SAFE_RADIUS
+      || dens.iter().any(|d| topology.distance(d, tile) <= 2 * TERRITORY_RADIUS) {
+      continue;
+    }
+    if rng.chance(den_chance(*kind) * density) {
+      dens.push(tile.clone());
+    }
+  }
+  dens
+}
+
+// puts wolf packs around dens picked by biome
+fn spawn_wildlife(world: &mut World, tile_map: Entity, density: f32, seed: &GameSeed) {
+  let mut rng = Rng::from_seed(seed, 5);
+  let topology = world.read_storage::<TileMap>().get(tile_map).map(|tm| tm.topology).unwrap_or_default();
+  let mut tiles: Vec<(Tile, TileMapTilesKind)> = {
+    let tiles = world.read_storage::<Tile>();
+    let kinds = world.read_storage::<TileMapTilesKind>();
+    let found = (&tiles, &kinds).join().map(|(t, k)| (t.clone(), *k)).collect();
+    found
+  };
+  tiles.sort_by_key(|(t, _)| (t.position.x, t.position.y));
+  let dens = pick_dens(topology, &tiles, density, &mut rng);
+
+  world.register::<Pack>();
+  for den in dens {
+    let mut spots: Vec<Tile> = vec![den.clone()];
+    spots.extend(topology.neighbours(&den).into_iter()
+      .filter(|n| tiles.iter().any(|(t, k)| t.position == n.position && den_chance(*k) > 0.0)));
+    let size = rng.range(2, MAX_PACK + 1) as usize;
+    let mut leader: Option<PawnId> = None;
+    for spot in spots.into_iter().take(size) {
+      let sex = if rng.chance(0.5) { Sex::Male } else { Sex::Female };
+      let at = match tile_center(world, &spot) {
+        None => continue,
+        Some(at) => at
+      };
+      let wolf = place_pawn(world, Spece::Wolf(sex), at, spot);
+      let id = world.read_storage::<PawnId>().get(wolf).cloned();
+      leader = leader.or(id);
+      if let Some(leader) = leader {
+        if let Err(err) = world.write_storage::<Pack>().insert(wolf, Pack { leader, den: den.clone() }) {
+          println!("cannot spawn pack: {}", err);
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dens_keep_territories_apart_and_away_from_the_colony() {
+    let mut tiles = Vec::new();
+    for x in -30..=30 {
+      for y in -30..=30 {
+        let kind = if x == 20 { TileMapTilesKind::Water } else { TileMapTilesKind::Grass };
+        tiles.push((Tile::new(x, y), kind));
+      }
+    }
+    // every land tile would get a den if there was room for it
+    let dens = pick_dens(GridTopology::Square4, &tiles, 100.0, &mut Rng::new(1));
+    let topology = GridTopology::Square4;
+    assert!(dens.len() > 1);
+    for (i, den) in dens.iter().enumerate() {
+      assert!(topology.distance(&Tile::new(0, 0), den) >= SAFE_RADIUS);
+      assert!(den.position.x != 20);
+      for other in dens.iter().skip(i + 1) {
+        assert!(topology.distance(den, other) > 2 * TERRITORY_RADIUS);
+      }
+    }
+  }
+}
@@ -0,0 +1,242 @@
+use std::collections::{ HashMap, HashSet };
+use amethyst::{
+  core::nalgebra::{ Vector3 },
+  ecs::{ Join, Read, ReadStorage, System, WriteStorage, Resources },
+};
+use super::{ Movement, Job, Jobs, Needs, Clock, Plant, step_cost };
+use super::combat::{ attack_target, REACH };
+use crate::game_state::TileMapTilesKind;
+use crate::pawn::{ Spece, PawnId };
+use crate::random::{ GameSeed, Rng };
+use crate::rendering::tile_map::{ Tile, TileMap, GridTopology, Elevation };
+
+const WANDER_CHANCE: f32 = 0.01;
+// pawns go to bed at night when a bit tired, during the day only when exhausted
+const SLEEP_AT_NIGHT: f32 = 0.8;
+const EXHAUSTED: f32 = 0.15;
+const RESTED_AT_DAY: f32 = 0.6;
+// hungry people go picking fruit they know about nearby
+const FORAGE_HUNGER: f32 = 0.6;
+const FORAGE_RANGE: i32 = 8;
+
+pub struct AiSystem {
+  rng: Rng,
+}
+
+impl AiSystem {
+  pub fn new() -> Self {
+    AiSystem {
+      rng: Rng::new(0),
+    }
+  }
+}
+
+pub type Terrain = HashMap<Vector3<i32>, (TileMapTilesKind, i32)>;
+
+pub fn build_terrain(
+  tiles: &ReadStorage<Tile>,
+  kinds: &ReadStorage<TileMapTilesKind>,
+  elevations: &ReadStorage<Elevation>
+) -> Terrain {
+  (tiles, kinds, elevations.maybe()).join()
+    .map(|(t, kind, e)| (t.position, (*kind, e.map(|e| e.0).unwrap_or(0))))
+    .collect()
+}
+
+pub fn can_step(map: &Terrain, from: &Tile, to: &Tile) -> bool {
+  let from_elevation = map.get(&from.position).map(|(_, e)| *e).unwrap_or(0);
+  map.get(&to.position)
+    .and_then(|(kind, e)| step_cost(*kind, from_elevation, *e))
+    .is_some()
+}
+
+pub fn walkable_path(topology: GridTopology, from: &Tile, to: &Tile, map: &Terrain) -> Option<Vec<Tile>> {
+  let line = topology.line(from, to);
+  if line.windows(2).all(|step| can_step(map, &step[0], &step[1])) {
+    Some(line.into_iter().skip(1).collect())
+  } else {
+    None
+  }
+}
+
+// neighbour getting closest to the target, or the farthest from it when running away
+pub fn step_towards(topology: GridTopology, map: &Terrain, from: &Tile, target: &Tile, away: bool) -> Option<Tile> {
+  let current = topology.distance(from, target);
+  topology.neighbours(from)
+    .into_iter()
+    .filter(|n| can_step(map, from, n))
+    .map(|n| (topology.distance(&n, target), n))
+    .filter(|(d, _)| if away { *d > current } else { *d < current })
+    .min_by_key(|(d, _)| if away { -d } else { *d })
+    .map(|(_, n)| n)
+}
+
+impl<'a> System<'a> for AiSystem {
+  type SystemData = (
+    Read<'a, Clock>,
+    ReadStorage<'a, Spece>,
+    ReadStorage<'a, PawnId>,
+    ReadStorage<'a, Tile>,
+    ReadStorage<'a, TileMapTilesKind>,
+    ReadStorage<'a, Elevation>,
+    ReadStorage<'a, TileMap>,
+    ReadStorage<'a, Needs>,
+    ReadStorage<'a, Plant>,
+    WriteStorage<'a, Movement>,
+    WriteStorage<'a, Jobs>,
+  );
+
+  fn run(&mut self, (clock, species, ids, tiles, kinds, elevations, maps, needs, plants, mut movements, mut jobs): Self::SystemData) {
+    let topology = (&maps).join().next().map(|tm| tm.topology).unwrap_or_default();
+    let map = build_terrain(&tiles, &kinds, &elevations);
+    let positions: HashMap<PawnId, Tile> = (&ids, &tiles).join()
+      .map(|(id, tile)| (*id, tile.clone()))
+      .collect();
+    let mut fruiting: Vec<Tile> = (&tiles, &plants).join()
+      .filter(|(_, plant)| plant.fruit > 0)
+      .map(|(tile, _)| tile.clone())
+      .collect();
+    fruiting.sort_by_key(|t| (t.position.x, t.position.y));
+
+    // wildlife has its own system
+    let mut pawns: Vec<_> = (&species, &ids, &tiles, (&needs).maybe(), &mut movements, &mut jobs).join()
+      .filter(|(spece, _, _, _, _, _)| !spece.is_animal())
+      .map(|(spece, id, tile, needs, movement, jobs)| (spece, id, tile, needs, movement, jobs))
+      .collect();
+    // prioritized pawns go first, id keeps the order stable for replays
+    pawns.sort_by_key(|(_, id, _, _, _, jobs)| (!jobs.prioritized, id.0));
+
+    // tiles somebody is already heading to during this tick
+    let mut claimed = HashSet::<Vector3<i32>>::new();
+    let night = clock.is_night();
+    // plants somebody is already going to pick
+    let mut picked = HashSet::<Vector3<i32>>::new();
+    for (spece, _id, tile, needs, movement, jobs) in pawns {
+      let rest = needs.map(|n| n.rest).unwrap_or(1.0);
+      let hunger = needs.map(|n| n.hunger).unwrap_or(1.0);
+      if jobs.is_sleeping() && (jobs.drafted || rest >= 1.0 || (!night && rest >= RESTED_AT_DAY)) {
+        jobs.current = None;
+      }
+      if !movement.is_idle() {
+        continue;
+      }
+      if let Some(Job::Wander) | Some(Job::MoveTo(_)) | Some(Job::Forage(_)) | Some(Job::Flee) = jobs.current {
+        jobs.current = None;
+      }
+      // chase one step at a time, so the way follows the target
+      if let Some(target) = attack_target(jobs) {
+        match positions.get(&target) {
+          None => jobs.current = None,
+          Some(at) => {
+            if topology.distance(tile, at) > REACH {
+              if let Some(step) = step_towards(topology, &map, tile, at, false) {
+                movement.path.push_back(step);
+              }
+            }
+            continue;
+          }
+        }
+      }
+      if jobs.current.is_some() {
+        continue;
+      }
+      if let Some(job) = jobs.queue.pop_front() {
+        if let Job::MoveTo(target) = &job {
+          match walkable_path(topology, tile, target, &map) {
+            None => continue,
+            Some(path) => movement.path.extend(path)
+          }
+        }
+        jobs.current = Some(job);
+        continue;
+      }
+      if !jobs.drafted && (rest < EXHAUSTED || (night && rest < SLEEP_AT_NIGHT)) {
+        jobs.current = Some(Job::Sleep);
+        continue;
+      }
+      if let (false, Spece::Human(_, _, _)) = (jobs.drafted, spece) {
+        if hunger < FORAGE_HUNGER {
+          let forage = fruiting.iter()
+            .filter(|plant| !picked.contains(&plant.position))
+            .filter(|plant| topology.distance(tile, plant) <= FORAGE_RANGE)
+            .filter_map(|plant| {
+              let path = if plant.position == tile.position {
+                Some(Vec::new())
+              } else {
+                walkable_path(topology, tile, plant, &map)
+              };
+              path.map(|p| (plant, p))
+            })
+            .min_by_key(|(_, path)| path.len());
+          if let Some((plant, path)) = forage {
+            picked.insert(plant.position);
+            movement.path.extend(path);
+            jobs.current = Some(Job::Forage(plant.clone()));
+            continue;
+          }
+        }
+      }
+      if jobs.drafted || !self.rng.chance(WANDER_CHANCE) {
+        continue;
+      }
+      let options: Vec<Tile> = topology.neighbours(tile)
+        .into_iter()
+        .filter(|n| can_step(&map, tile, n) && !claimed.contains(&n.position))
+        .collect();
+      if options.is_empty() {
+        continue;
+      }
+      let choice = self.rng.range(0, options.len() as i32) as usize;
+      claimed.insert(options[choice].position);
+      movement.path.push_back(options[choice].clone());
+      jobs.current = Some(Job::Wander);
+    }
+  }
+
+  fn setup(&mut self, res: &mut Resources) {
+    use amethyst::ecs::SystemData;
+    Self::SystemData::setup(res);
+    self.rng = Rng::from_seed(&res.entry::<GameSeed>().or_insert_with(GameSeed::default), 1);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn flat(water: &[(i32, i32)]) -> Terrain {
+    let mut map = Terrain::new();
+    for x in -5..=5 {
+      for y in -5..=5 {
+        let kind = if water.contains(&(x, y)) { TileMapTilesKind::Water } else { TileMapTilesKind::Grass };
+        map.insert(Tile::new(x, y).position, (kind, 0));
+      }
+    }
+    map
+  }
+
+  fn step(map: &Terrain, to: (i32, i32), away: bool) -> Option<(i32, i32)> {
+    step_towards(GridTopology::Square4, map, &Tile::new(0, 0), &Tile::new(to.0, to.1), away)
+      .map(|t| (t.position.x, t.position.y))
+  }
+
+  #[test]
+  fn steps_toward_the_target() {
+    assert_eq!(step(&flat(&[]), (3, 0), false), Some((1, 0)));
+    assert_eq!(step(&flat(&[]), (0, -3), false), Some((0, -1)));
+  }
+
+  #[test]
+  fn steps_away_from_the_threat() {
+    let away = step(&flat(&[]), (3, 0), true).unwrap();
+    assert_eq!(GridTopology::Square4.distance(&Tile::new(away.0, away.1), &Tile::new(3, 0)), 4);
+    assert_eq!(step(&flat(&[(0, 1), (0, -1)]), (3, 0), true), Some((-1, 0)));
+  }
+
+  #[test]
+  fn water_blocks_the_step() {
+    assert_eq!(step(&flat(&[(1, 0)]), (3, 0), false), None);
+    // nowhere farther to run to
+    assert_eq!(step(&flat(&[(-1, 0), (0, 1), (0, -1)]), (3, 0), true), None);
+  }
+}
@@ -0,0 +1,218 @@
+use amethyst::ecs::{ Read, System, Write };
+use serde_derive::{ Serialize, Deserialize };
+use super::SimTime;
+
+pub const MINUTES_PER_DAY: u32 = 24 * 60;
+pub const DAYS_PER_SEASON: u32 = 15;
+// half a second of normal speed per in-game minute, a day takes twelve minutes
+pub const TICKS_PER_MINUTE: u64 = 15;
+
+const DAWN: u32 = 5 * 60;
+const DAY: u32 = 8 * 60;
+const DUSK: u32 = 18 * 60;
+const NIGHT: u32 = 21 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DayPhase {
+  Dawn,
+  Day,
+  Dusk,
+  Night,
+}
+
+// minute of the day, light color and intensity, light is interpolated between them
+const LIGHT_KEYS: [(u32, [f32; 3], f32); 8] = [
+  (0, [0.35, 0.4, 0.75], 0.35),
+  (DAWN, [0.35, 0.4, 0.75], 0.35),
+  (6 * 60, [1.0, 0.7, 0.5], 0.7),
+  (DAY, [1.0, 1.0, 1.0], 1.0),
+  (DUSK, [1.0, 1.0, 1.0], 1.0),
+  (19 * 60 + 30, [1.0, 0.6, 0.4], 0.7),
+  (NIGHT, [0.35, 0.4, 0.75], 0.35),
+  (MINUTES_PER_DAY, [0.35, 0.4, 0.75], 0.35),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Season {
+  Spring,
+  Summer,
+  Autumn,
+  Winter,
+}
+
+impl Default for Season {
+  fn default() -> Self {
+    Season::Spring
+  }
+}
+
+impl Season {
+  pub fn next(&self) -> Season {
+    match self {
+      Season::Spring => Season::Summer,
+      Season::Summer => Season::Autumn,
+      Season::Autumn => Season::Winter,
+      Season::Winter => Season::Spring,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Clock {
+  // minute of the day
+  pub minute: u32,
+  // day since the start of the season
+  pub day: u32,
+  pub season: Season,
+  pub year: u32,
+}
+
+impl Default for Clock {
+  fn default() -> Self {
+    Clock {
+      minute: 8 * 60,
+      day: 0,
+      season: Season::default(),
+      year: 0,
+    }
+  }
+}
+
+impl Clock {
+  pub fn hour(&self) -> u32 {
+    self.minute / 60
+  }
+
+  pub fn advance(&mut self) {
+    self.minute += 1;
+    if self.minute < MINUTES_PER_DAY {
+      return;
+    }
+    self.minute = 0;
+    self.day += 1;
+    if self.day < DAYS_PER_SEASON {
+      return;
+    }
+    self.day = 0;
+    self.season = self.season.next();
+    if self.season == Season::Spring {
+      self.year += 1;
+    }
+  }
+
+  pub fn phase(&self) -> DayPhase {
+    match self.minute {
+      m if m < DAWN => DayPhase::Night,
+      m if m < DAY => DayPhase::Dawn,
+      m if m < DUSK => DayPhase::Day,
+      m if m < NIGHT => DayPhase::Dusk,
+      _ => DayPhase::Night,
+    }
+  }
+
+  pub fn is_night(&self) -> bool {
+    self.phase() == DayPhase::Night
+  }
+
+  // color and intensity of the sky light
+  pub fn light(&self) -> ([f32; 3], f32) {
+    let next = LIGHT_KEYS.iter()
+      .position(|(m, _, _)| *m > self.minute)
+      .unwrap_or(LIGHT_KEYS.len() - 1);
+    let (from, from_color, from_intensity) = LIGHT_KEYS[next - 1];
+    let (to, to_color, to_intensity) = LIGHT_KEYS[next];
+    let t = (self.minute - from) as f32 / (to - from).max(1) as f32;
+    let mut color = [0.0; 3];
+    for i in 0..3 {
+      color[i] = from_color[i] + (to_color[i] - from_color[i]) * t;
+    }
+    (color, from_intensity + (to_intensity - from_intensity) * t)
+  }
+
+  pub fn describe(&self) -> String {
+    format!(
+      "{:02}:{:02} {:?}, day {} of {:?}, year {}",
+      self.hour(), self.minute % 60, self.phase(), self.day + 1, self.season, self.year + 1
+    )
+  }
+}
+
+pub struct ClockSystem;
+
+impl<'a> System<'a> for ClockSystem {
+  type SystemData = (
+    Read<'a, SimTime>,
+    Write<'a, Clock>,
+  );
+
+  fn run(&mut self, (time, mut clock): Self::SystemData) {
+    if time.tick % TICKS_PER_MINUTE == 0 {
+      clock.advance();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn at(minute: u32) -> Clock {
+    Clock { minute, ..Clock::default() }
+  }
+
+  #[test]
+  fn day_rolls_over_at_midnight() {
+    let mut clock = at(MINUTES_PER_DAY - 1);
+    clock.advance();
+    assert_eq!((clock.minute, clock.day, clock.season), (0, 1, Season::Spring));
+  }
+
+  #[test]
+  fn season_and_year_roll_over() {
+    let mut clock = Clock { minute: MINUTES_PER_DAY - 1, day: DAYS_PER_SEASON - 1, season: Season::Autumn, year: 2 };
+    clock.advance();
+    assert_eq!((clock.day, clock.season, clock.year), (0, Season::Winter, 2));
+    clock = Clock { minute: MINUTES_PER_DAY - 1, day: DAYS_PER_SEASON - 1, season: Season::Winter, year: 2 };
+    clock.advance();
+    assert_eq!((clock.day, clock.season, clock.year), (0, Season::Spring, 3));
+  }
+
+  #[test]
+  fn whole_year_passes() {
+    let mut clock = at(0);
+    for _ in 0..MINUTES_PER_DAY * DAYS_PER_SEASON * 4 {
+      clock.advance();
+    }
+    assert_eq!((clock.minute, clock.day, clock.season, clock.year), (0, 0, Season::Spring, 1));
+  }
+
+  #[test]
+  fn light_matches_the_keys() {
+    for (minute, color, intensity) in LIGHT_KEYS.iter().take(LIGHT_KEYS.len() - 1) {
+      assert_eq!(at(*minute).light(), (*color, *intensity));
+    }
+    assert_eq!(at(12 * 60).light(), ([1.0, 1.0, 1.0], 1.0));
+  }
+
+  #[test]
+  fn light_is_interpolated_and_continuous_over_midnight() {
+    let (_, halfway) = at((DAWN + 6 * 60) / 2).light();
+    assert!(halfway > 0.35 && halfway < 0.7);
+    let (late_color, late) = at(MINUTES_PER_DAY - 1).light();
+    let (early_color, early) = at(0).light();
+    assert!((late - early).abs() < 1e-3);
+    for i in 0..3 {
+      assert!((late_color[i] - early_color[i]).abs() < 1e-3);
+    }
+  }
+
+  #[test]
+  fn phases_follow_the_day() {
+    assert_eq!(at(0).phase(), DayPhase::Night);
+    assert_eq!(at(DAWN).phase(), DayPhase::Dawn);
+    assert_eq!(at(DAY).phase(), DayPhase::Day);
+    assert_eq!(at(DUSK).phase(), DayPhase::Dusk);
+    assert_eq!(at(NIGHT).phase(), DayPhase::Night);
+  }
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use amethyst::{
+  ecs::{ Component, DenseVecStorage, Entities, Entity, Join, Read, ReadStorage, System, WriteStorage },
+};
+use super::{ SimTime, Job, Jobs };
+use crate::pawn::{ Spece, PawnId };
+use crate::rendering::tile_map::{ Tile, TileMap };
+use crate::resource::{ Resource, ResourceStack };
+
+// attacks reach this many steps
+pub const REACH: i32 = 1;
+// health recovered per in-game second out of fights
+const HEAL_RATE: f32 = 0.004;
+
+// 1 is healthy, the pawn dies at 0
+pub struct Health(pub f32);
+
+impl Default for Health {
+  fn default() -> Self {
+    Health(1.0)
+  }
+}
+
+impl Component for Health {
+  type Storage = DenseVecStorage<Self>;
+}
+
+// health taken per in-game second of fighting
+pub fn attack_damage(spece: &Spece) -> f32 {
+  match spece {
+    Spece::Wolf(_) => 0.06,
+    Spece::Human(_, _, _) => 0.05,
+  }
+}
+
+// pieces of meat left by the corpse
+pub fn meat_yield(spece: &Spece) -> u32 {
+  match spece {
+    Spece::Wolf(_) => 3,
+    Spece::Human(_, _, _) => 2,
+  }
+}
+
+pub fn attack_target(jobs: &Jobs) -> Option<PawnId> {
+  match jobs.current {
+    Some(Job::Attack(target)) => Some(target),
+    _ => None,
+  }
+}
+
+// Attackers next to their target hurt it, pawns out of fights slowly heal. Attacked pawns which are awake hit back,
+// unless they are already attacking the attacker themselves.
+pub struct CombatSystem;
+
+impl<'a> System<'a> for CombatSystem {
+  type SystemData = (
+    Read<'a, SimTime>,
+    Entities<'a>,
+    ReadStorage<'a, TileMap>,
+    ReadStorage<'a, Spece>,
+    ReadStorage<'a, PawnId>,
+    ReadStorage<'a, Tile>,
+    ReadStorage<'a, Jobs>,
+    WriteStorage<'a, Health>,
+  );
+
+  fn run(&mut self, (time, entities, maps, species, ids, tiles, jobs, mut healths): Self::SystemData) {
+    let topology = (&maps).join().next().map(|tm| tm.topology).unwrap_or_default();
+    let pawns: HashMap<PawnId, (Entity, &Spece, &Tile, &Jobs)> = (&entities, &species, &ids, &tiles, &jobs).join()
+      .map(|(e, spece, id, tile, jobs)| (*id, (e, spece, tile, jobs)))
+      .collect();
+    let mut attacks: Vec<(PawnId, PawnId)> = pawns.iter()
+      .filter_map(|(id, (_, _, _, jobs))| attack_target(jobs).map(|target| (*id, target)))
+      .collect();
+    attacks.sort_by_key(|(id, _)| id.0);
+
+    let dt = time.tick_seconds;
+    let mut damage: Vec<(Entity, f32)> = Vec::new();
+    for (attacker, target) in attacks {
+      let (a, a_spece, a_tile, _) = pawns[&attacker];
+      let (t, t_spece, t_tile, t_jobs) = match pawns.get(&target) {
+        None => continue,
+        Some(p) => *p
+      };
+      if topology.distance(a_tile, t_tile) > REACH {
+        continue;
+      }
+      damage.push((t, attack_damage(a_spece) * dt));
+      if !t_jobs.is_sleeping() && attack_target(t_jobs) != Some(attacker) {
+        damage.push((a, attack_damage(t_spece) * dt));
+      }
+    }
+    for (e, health) in (&entities, &mut healths).join() {
+      if !damage.iter().any(|(d, _)| *d == e) {
+        health.0 = (health.0 + HEAL_RATE * dt).min(1.0);
+      }
+    }
+    for (e, amount) in damage {
+      if let Some(health) = healths.get_mut(e) {
+        health.0 -= amount;
+      }
+    }
+  }
+}
+
+// removes killed pawns, their corpses turn into meat on the tile
+pub struct DeathSystem;
+
+impl<'a> System<'a> for DeathSystem {
+  type SystemData = (
+    Entities<'a>,
+    ReadStorage<'a, Spece>,
+    ReadStorage<'a, PawnId>,
+    ReadStorage<'a, Health>,
+    WriteStorage<'a, Tile>,
+    WriteStorage<'a, ResourceStack>,
+  );
+
+  fn run(&mut self, (entities, species, ids, healths, mut tiles, mut stacks): Self::SystemData) {
+    let mut dead: Vec<(Entity, PawnId, Spece, Tile)> = (&entities, &species, &ids, &healths, &tiles).join()
+      .filter(|(_, _, _, health, _)| health.0 <= 0.0)
+      .map(|(e, spece, id, _, tile)| (e, *id, *spece, tile.clone()))
+      .collect();
+    dead.sort_by_key(|(_, id, _, _)| id.0);
+    for (e, _, spece, tile) in dead {
+      if let Err(err) = entities.delete(e) {
+        println!("cannot remove dead pawn: {}", err);
+      }
+      let meat = meat_yield(&spece);
+      if meat == 0 {
+        continue;
+      }
+      let existing = (&mut stacks, &tiles).join()
+        .find(|(s, t)| s.resource == Resource::PieceOfMeat && s.count > 0 && t.position == tile.position)
+        .map(|(s, _)| s);
+      match existing {
+        Some(stack) => stack.count += meat,
+        None => {
+          let corpse = entities.create();
+          let results = [
+            stacks.insert(corpse, ResourceStack { resource: Resource::PieceOfMeat, count: meat }).map(|_| ()),
+            tiles.insert(corpse, tile).map(|_| ()),
+          ];
+          for r in results.iter() {
+            if let Err(err) = r {
+              println!("cannot leave meat: {}", err);
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use amethyst::{
+    core::nalgebra::Vector2,
+    ecs::{ Builder, RunNow, World },
+  };
+  use crate::pawn::{ Sex, Race, Complex };
+  use crate::rendering::tile_map::GridTopology;
+  use super::*;
+
+  const WOLF: Spece = Spece::Wolf(Sex::Female);
+  const HUMAN: Spece = Spece::Human(Sex::Male, Race::Euro, Complex::Athletic);
+
+  fn combat_world() -> World {
+    let mut world = World::new();
+    world.register::<TileMap>();
+    world.register::<Spece>();
+    world.register::<PawnId>();
+    world.register::<Tile>();
+    world.register::<Jobs>();
+    world.register::<Health>();
+    world.register::<ResourceStack>();
+    // a whole second passes every tick
+    world.add_resource(SimTime { tick: 0, tick_seconds: 1.0 });
+    world.create_entity()
+      .with(TileMap::new(GridTopology::Square4, Vector2::new(1.0, 1.0)))
+      .build();
+    world
+  }
+
+  fn pawn(world: &mut World, id: u32, spece: Spece, at: (i32, i32), job: Option<Job>, health: f32) -> Entity {
+    let mut jobs = Jobs::default();
+    jobs.current = job;
+    world.create_entity()
+      .with(PawnId(id))
+      .with(spece)
+      .with(Tile::new(at.0, at.1))
+      .with(jobs)
+      .with(Health(health))
+      .build()
+  }
+
+  fn health(world: &World, e: Entity) -> f32 {
+    world.read_storage::<Health>().get(e).unwrap().0
+  }
+
+  fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-5, "{} is not {}", a, b);
+  }
+
+  #[test]
+  fn attacked_pawn_hits_back() {
+    let mut world = combat_world();
+    let wolf = pawn(&mut world, 1, WOLF, (0, 0), Some(Job::Attack(PawnId(2))), 1.0);
+    let human = pawn(&mut world, 2, HUMAN, (1, 0), None, 1.0);
+    CombatSystem.run_now(&world.res);
+    assert_close(health(&world, human), 1.0 - attack_damage(&WOLF));
+    assert_close(health(&world, wolf), 1.0 - attack_damage(&HUMAN));
+  }
+
+  #[test]
+  fn mutual_attackers_hit_each_other_once() {
+    let mut world = combat_world();
+    let wolf = pawn(&mut world, 1, WOLF, (0, 0), Some(Job::Attack(PawnId(2))), 1.0);
+    let human = pawn(&mut world, 2, HUMAN, (1, 0), Some(Job::Attack(PawnId(1))), 1.0);
+    CombatSystem.run_now(&world.res);
+    assert_close(health(&world, human), 1.0 - attack_damage(&WOLF));
+    assert_close(health(&world, wolf), 1.0 - attack_damage(&HUMAN));
+  }
+
+  #[test]
+  fn sleeping_and_distant_pawns() {
+    let mut world = combat_world();
+    let wolf = pawn(&mut world, 1, WOLF, (0, 0), Some(Job::Attack(PawnId(2))), 0.5);
+    let sleeper = pawn(&mut world, 2, HUMAN, (1, 0), Some(Job::Sleep), 1.0);
+    let hunter = pawn(&mut world, 3, HUMAN, (5, 0), Some(Job::Attack(PawnId(1))), 0.5);
+    CombatSystem.run_now(&world.res);
+    // the sleeper does not hit back, the hunter is out of reach and heals instead
+    assert_close(health(&world, sleeper), 1.0 - attack_damage(&WOLF));
+    assert_close(health(&world, wolf), 0.5 + HEAL_RATE);
+    assert_close(health(&world, hunter), 0.5 + HEAL_RATE);
+  }
+
+  fn meat(world: &World) -> Vec<u32> {
+    let mut counts: Vec<u32> = world.read_storage::<ResourceStack>().join()
+      .filter(|s| s.resource == Resource::PieceOfMeat)
+      .map(|s| s.count)
+      .collect();
+    counts.sort();
+    counts
+  }
+
+  #[test]
+  fn corpse_meat_goes_on_the_stack_lying_there() {
+    let mut world = combat_world();
+    let wolf = pawn(&mut world, 1, WOLF, (0, 0), None, 0.0);
+    world.create_entity()
+      .with(ResourceStack { resource: Resource::PieceOfMeat, count: 2 })
+      .with(Tile::new(0, 0))
+      .build();
+    DeathSystem.run_now(&world.res);
+    world.maintain();
+    assert!(!world.is_alive(wolf));
+    assert_eq!(meat(&world), vec![2 + meat_yield(&WOLF)]);
+  }
+
+  #[test]
+  fn corpse_meat_starts_a_new_stack() {
+    let mut world = combat_world();
+    pawn(&mut world, 1, HUMAN, (0, 0), None, -0.1);
+    pawn(&mut world, 2, WOLF, (1, 0), None, 0.1);
+    // meat eaten up to the last piece is not added to
+    world.create_entity()
+      .with(ResourceStack { resource: Resource::PieceOfMeat, count: 0 })
+      .with(Tile::new(0, 0))
+      .build();
+    DeathSystem.run_now(&world.res);
+    world.maintain();
+    assert_eq!(meat(&world), vec![0, meat_yield(&HUMAN)]);
+  }
+}
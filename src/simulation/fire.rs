@@ -0,0 +1,85 @@
+use amethyst::{
+  core::{ nalgebra::Vector2, transform::Transform },
+  ecs::{ Builder, Component, DenseVecStorage, World },
+  renderer::{ SpriteRender, SpriteSheetHandle },
+};
+use serde_derive::{ Serialize, Deserialize };
+use crate::asset_loader::{ load_png_texture, load_ss_asset };
+use crate::rendering::lighting::LightSource;
+use crate::rendering::tile_map::{ Tile, tile_center };
+
+const FIRE_SCALE: f32 = 0.6;
+// sprites are 64 pixels high
+const FIRE_HEIGHT: f32 = 64.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Fire {
+  Campfire,
+  Torch,
+}
+
+impl Component for Fire {
+  type Storage = DenseVecStorage<Self>;
+}
+
+impl Fire {
+  pub fn light(&self) -> LightSource {
+    match self {
+      Fire::Campfire => LightSource { color: [1.0, 0.6, 0.3], intensity: 0.9, radius: 4 },
+      Fire::Torch => LightSource { color: [1.0, 0.75, 0.45], intensity: 0.6, radius: 2 },
+    }
+  }
+
+  fn sprite_number(&self) -> usize {
+    match self {
+      Fire::Campfire => 0,
+      Fire::Torch => 1,
+    }
+  }
+
+  // degrees added to the tile of the fire and tiles it reaches, fading with distance
+  pub fn heat(&self) -> (f32, i32) {
+    match self {
+      Fire::Campfire => (30.0, 3),
+      Fire::Torch => (6.0, 1),
+    }
+  }
+}
+
+// sprite sheet of fires, headless simulation goes without it
+pub struct FireSprites {
+  pub sprite_sheet: SpriteSheetHandle,
+}
+
+pub fn load_fire_sprites(world: &mut World) {
+  let texture = load_png_texture(world, "resources/sprites/fire/fires.png".to_string());
+  let sprite_sheet = load_ss_asset(world, "resources/sprites/fire/sprite_sheet.ron".to_string(), texture);
+  world.add_resource(FireSprites { sprite_sheet });
+}
+
+// fire stands on the tile center, just below pawns
+fn fire_transform(at: Vector2<f32>) -> Transform {
+  let mut transform: Transform = Default::default();
+  transform.set_scale(FIRE_SCALE, FIRE_SCALE, 1.0);
+  transform.set_xyz(at.x, at.y + FIRE_HEIGHT * FIRE_SCALE / 2.0, 0.8);
+  transform
+}
+
+pub fn place_fire(world: &mut World, fire: Fire, tile: Tile) {
+  world.register::<Fire>();
+  world.register::<LightSource>();
+  world.register::<Tile>();
+  let sprite = world.res.try_fetch::<FireSprites>().map(|sprites| SpriteRender {
+    sprite_sheet: sprites.sprite_sheet.clone(),
+    sprite_number: fire.sprite_number(),
+  });
+  let at = tile_center(world, &tile);
+  let entity = world.create_entity()
+    .with(fire)
+    .with(fire.light())
+    .with(tile);
+  match (sprite, at) {
+    (Some(s), Some(at)) => entity.with(s).with(fire_transform(at)).build(),
+    _ => entity.build()
+  };
+}
@@ -0,0 +1,41 @@
+use std::collections::VecDeque;
+use amethyst::ecs::{ Component, DenseVecStorage };
+use serde_derive::{ Serialize, Deserialize };
+use crate::pawn::PawnId;
+use crate::rendering::tile_map::Tile;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Job {
+  Wander,
+  MoveTo(Tile),
+  Sleep,
+  // pick the fruit of the plant on the tile
+  Forage(Tile),
+  // chase the pawn and fight it
+  Attack(PawnId),
+  // run away from danger
+  Flee,
+}
+
+#[derive(Default)]
+pub struct Jobs {
+  pub current: Option<Job>,
+  pub queue: VecDeque<Job>,
+  // drafted pawns do nothing on their own, only what they are ordered
+  pub drafted: bool,
+  // prioritized pawns pick their jobs before others
+  pub prioritized: bool,
+}
+
+impl Jobs {
+  pub fn is_sleeping(&self) -> bool {
+    match self.current {
+      Some(Job::Sleep) => true,
+      _ => false,
+    }
+  }
+}
+
+impl Component for Jobs {
+  type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,110 @@
+use amethyst::{
+  core::Time,
+  ecs::{ Dispatcher, DispatcherBuilder, World },
+};
+
+pub mod needs;
+pub mod movement;
+pub mod ai;
+pub mod speed;
+pub mod clock;
+pub mod jobs;
+pub mod sight;
+pub mod fire;
+pub mod temperature;
+pub mod weather;
+pub mod plants;
+pub mod combat;
+pub mod wildlife;
+pub use self::needs::{ Need, Needs, NeedsSystem };
+pub use self::speed::{ GameSpeed, GameSpeedSystem };
+pub use self::clock::{ Clock, ClockSystem, DayPhase, Season };
+pub use self::jobs::{ Job, Jobs };
+pub use self::movement::{ Movement, MovementSystem, terrain_cost, step_cost, MAX_CLIMB };
+pub use self::ai::AiSystem;
+pub use self::fire::{ Fire, place_fire, load_fire_sprites };
+pub use self::plants::{ Plant, PlantKind, GrowthStage, PlantGrowthSystem, ForageSystem, place_plant, load_plant_sprites };
+pub use self::weather::{ Weather, WeatherState, WeatherSystem };
+pub use self::temperature::{ Temperature, TemperatureSystem, FreezeSystem, WarmthSystem };
+pub use self::combat::{ Health, CombatSystem, DeathSystem };
+pub use self::wildlife::{ Pack, WildlifeSystem, TERRITORY_RADIUS };
+pub use self::sight::{ line_of_sight, Visibility, TileVisibility, VisionSystem };
+
+pub const TICKS_PER_SECOND: u32 = 30;
+const MAX_TICKS_PER_FRAME: u32 = 8;
+// ticks run per frame while a loaded session catches up to the tick it was saved at
+pub const CATCH_UP_TICKS_PER_FRAME: u64 = 240;
+
+pub struct SimTime {
+  pub tick: u64,
+  pub tick_seconds: f32,
+}
+
+impl Default for SimTime {
+  fn default() -> Self {
+    SimTime {
+      tick: 0,
+      tick_seconds: 1.0 / TICKS_PER_SECOND as f32,
+    }
+  }
+}
+
+pub struct Simulation {
+  dispatcher: Dispatcher<'static, 'static>,
+  accumulator: f32,
+}
+
+impl Simulation {
+  pub fn new(world: &mut World) -> Self {
+    let mut dispatcher = DispatcherBuilder::new()
+      .with(ClockSystem, "clock_system", &[])
+      .with(NeedsSystem, "needs_system", &["clock_system"])
+      .with(WeatherSystem, "weather_system", &["clock_system"])
+      .with(TemperatureSystem, "temperature_system", &["weather_system"])
+      .with(FreezeSystem, "freeze_system", &["temperature_system"])
+      .with(WarmthSystem, "warmth_system", &["temperature_system", "needs_system"])
+      .with(AiSystem::new(), "ai_system", &["warmth_system", "freeze_system"])
+      .with(WildlifeSystem::new(), "wildlife_system", &["ai_system"])
+      .with(MovementSystem, "movement_system", &["ai_system", "wildlife_system"])
+      .with(VisionSystem, "vision_system", &["movement_system"])
+      .with(PlantGrowthSystem, "plant_growth_system", &["temperature_system"])
+      .with(ForageSystem, "forage_system", &["movement_system", "plant_growth_system"])
+      .with(CombatSystem, "combat_system", &["movement_system"])
+      .with(DeathSystem, "death_system", &["combat_system", "forage_system"])
+      .build();
+    world.add_resource(SimTime::default());
+    world.add_resource(Visibility::default());
+    world.res.entry::<GameSpeed>().or_insert_with(GameSpeed::default);
+    world.res.entry::<Clock>().or_insert_with(Clock::default);
+    world.add_resource(Temperature::default());
+    world.res.entry::<WeatherState>().or_insert_with(WeatherState::default);
+    dispatcher.setup(&mut world.res);
+    Simulation {
+      dispatcher,
+      accumulator: 0.0,
+    }
+  }
+
+  pub fn tick(&mut self, world: &mut World) {
+    self.dispatcher.dispatch(&world.res);
+    world.maintain();
+    world.write_resource::<SimTime>().tick += 1;
+  }
+
+  // Amount of fixed ticks frame time allows. Leftover time is kept for the next frame.
+  pub fn due_ticks(&mut self, world: &World) -> u32 {
+    let (delta, tick_seconds, multiplier) = {
+      let time = world.read_resource::<Time>();
+      let sim_time = world.read_resource::<SimTime>();
+      let speed = world.read_resource::<GameSpeed>();
+      (time.delta_seconds(), sim_time.tick_seconds, speed.multiplier())
+    };
+    self.accumulator += delta * multiplier as f32;
+    let mut ticks = 0;
+    while self.accumulator >= tick_seconds {
+      self.accumulator -= tick_seconds;
+      ticks += 1;
+    }
+    ticks.min(MAX_TICKS_PER_FRAME * multiplier)
+  }
+}
@@ -0,0 +1,143 @@
+use std::collections::{ HashMap, VecDeque };
+use amethyst::{
+  core::{ nalgebra::Vector3, transform::Transform },
+  ecs::{ Component, DenseVecStorage, Join, Read, ReadStorage, System, WriteStorage },
+};
+use super::{ SimTime, WeatherState };
+use crate::game_state::TileMapTilesKind;
+use crate::pawn::pawn_translation;
+use crate::rendering::tile_map::{ Tile, TileMap, Elevation };
+
+pub struct Movement {
+  pub path: VecDeque<Tile>,
+  pub progress: f32,
+  // tiles per second
+  pub speed: f32,
+}
+
+impl Default for Movement {
+  fn default() -> Self {
+    Movement {
+      path: VecDeque::new(),
+      progress: 0.0,
+      speed: 1.5,
+    }
+  }
+}
+
+impl Movement {
+  pub fn is_idle(&self) -> bool {
+    self.path.is_empty()
+  }
+}
+
+impl Component for Movement {
+  type Storage = DenseVecStorage<Self>;
+}
+
+// time multiplier to walk into a tile, None if the tile can't be walked at all
+pub fn terrain_cost(kind: TileMapTilesKind) -> Option<f32> {
+  match kind {
+    TileMapTilesKind::Grass => Some(1.0),
+    TileMapTilesKind::Dirt => Some(1.2),
+    TileMapTilesKind::Ice => Some(1.5),
+    TileMapTilesKind::Water => None,
+  }
+}
+
+// higher steps are cliffs, nobody climbs them
+pub const MAX_CLIMB: i32 = 1;
+const CLIMB_COST: f32 = 0.5;
+
+// cost of a step between neighbour tiles, climbing up or down is slower
+pub fn step_cost(kind: TileMapTilesKind, from_elevation: i32, to_elevation: i32) -> Option<f32> {
+  let climb = (to_elevation - from_elevation).abs();
+  if climb > MAX_CLIMB {
+    return None;
+  }
+  terrain_cost(kind).map(|c| c + CLIMB_COST * climb as f32)
+}
+
+pub struct MovementSystem;
+
+impl<'a> System<'a> for MovementSystem {
+  type SystemData = (
+    Read<'a, SimTime>,
+    Read<'a, WeatherState>,
+    ReadStorage<'a, TileMap>,
+    ReadStorage<'a, TileMapTilesKind>,
+    ReadStorage<'a, Elevation>,
+    WriteStorage<'a, Tile>,
+    WriteStorage<'a, Movement>,
+    WriteStorage<'a, Transform>,
+  );
+
+  fn run(&mut self, (time, weather, tile_map, kinds, elevations, mut tiles, mut movements, mut transforms): Self::SystemData) {
+    let tm = match (&tile_map).join().next() {
+      None => return,
+      Some(tm) => tm
+    };
+    let terrain: HashMap<Vector3<i32>, (TileMapTilesKind, i32)> = (&tiles, &kinds, (&elevations).maybe()).join()
+      .map(|(t, k, e)| (t.position, (*k, e.map(|e| e.0).unwrap_or(0))))
+      .collect();
+    let elevation_of = |t: &Tile| terrain.get(&t.position).map(|(_, e)| *e).unwrap_or(0);
+    for (tile, movement, transform) in (&mut tiles, &mut movements, &mut transforms).join() {
+      let next = match movement.path.front() {
+        None => continue,
+        Some(next) => next.clone()
+      };
+      // map may change under the walker, path is dropped when the step became impossible
+      let cost = terrain.get(&next.position)
+        .and_then(|(kind, e)| step_cost(*kind, elevation_of(tile), *e));
+      let cost = match cost {
+        None => {
+          movement.path.clear();
+          movement.progress = 0.0;
+          continue;
+        }
+        Some(c) => c
+      };
+      movement.progress += movement.speed * time.tick_seconds / (cost * weather.current.movement_factor());
+      if movement.progress >= 1.0 {
+        movement.progress = 0.0;
+        movement.path.pop_front();
+        *tile = next.clone();
+      }
+      let from = tm.tile_center(tile, elevation_of(tile));
+      let to = tm.tile_center(&next, elevation_of(&next));
+      let at = pawn_translation(from + (to - from) * movement.progress);
+      transform.set_xyz(at.x, at.y, at.z);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn flat_step_costs_the_terrain() {
+    assert_eq!(step_cost(TileMapTilesKind::Grass, 0, 0), Some(1.0));
+    assert_eq!(step_cost(TileMapTilesKind::Dirt, 2, 2), Some(1.2));
+    assert_eq!(step_cost(TileMapTilesKind::Ice, 2, 2), Some(1.5));
+  }
+
+  #[test]
+  fn climbing_is_slower_both_ways() {
+    let up = step_cost(TileMapTilesKind::Grass, 0, MAX_CLIMB);
+    let down = step_cost(TileMapTilesKind::Grass, MAX_CLIMB, 0);
+    assert_eq!(up, Some(1.0 + CLIMB_COST * MAX_CLIMB as f32));
+    assert_eq!(up, down);
+  }
+
+  #[test]
+  fn cliffs_cannot_be_climbed() {
+    assert_eq!(step_cost(TileMapTilesKind::Grass, 0, MAX_CLIMB + 1), None);
+    assert_eq!(step_cost(TileMapTilesKind::Grass, MAX_CLIMB + 1, 0), None);
+  }
+
+  #[test]
+  fn water_cannot_be_walked() {
+    assert_eq!(step_cost(TileMapTilesKind::Water, 0, 0), None);
+  }
+}
@@ -0,0 +1,75 @@
+use amethyst::ecs::{ Component, DenseVecStorage, Join, Read, ReadStorage, System, WriteStorage };
+use serde_derive::{ Serialize, Deserialize };
+use super::{ SimTime, Clock, Jobs };
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Need {
+  Hunger,
+  Rest,
+  Warmth,
+}
+
+// every value is in 0..1 range, 1 means fully satisfied
+#[derive(Clone, Copy, Debug)]
+pub struct Needs {
+  pub hunger: f32,
+  pub rest: f32,
+  pub warmth: f32,
+}
+
+impl Default for Needs {
+  fn default() -> Self {
+    Needs {
+      hunger: 1.0,
+      rest: 1.0,
+      warmth: 1.0,
+    }
+  }
+}
+
+impl Needs {
+  pub fn set(&mut self, need: Need, value: f32) {
+    let value = value.max(0.0).min(1.0);
+    match need {
+      Need::Hunger => self.hunger = value,
+      Need::Rest => self.rest = value,
+      Need::Warmth => self.warmth = value,
+    }
+  }
+}
+
+impl Component for Needs {
+  type Storage = DenseVecStorage<Self>;
+}
+
+// per in-game second
+const HUNGER_DECAY: f32 = 1.0 / 600.0;
+const REST_DECAY: f32 = 1.0 / 900.0;
+// staying awake at night is more tiring
+const NIGHT_FATIGUE: f32 = 2.0;
+const REST_RECOVERY: f32 = 1.0 / 300.0;
+
+pub struct NeedsSystem;
+
+impl<'a> System<'a> for NeedsSystem {
+  type SystemData = (
+    Read<'a, SimTime>,
+    Read<'a, Clock>,
+    ReadStorage<'a, Jobs>,
+    WriteStorage<'a, Needs>,
+  );
+
+  fn run(&mut self, (time, clock, jobs, mut needs): Self::SystemData) {
+    let dt = time.tick_seconds;
+    let fatigue = if clock.is_night() { NIGHT_FATIGUE } else { 1.0 };
+    for (need, jobs) in (&mut needs, (&jobs).maybe()).join() {
+      need.hunger = (need.hunger - HUNGER_DECAY * dt).max(0.0);
+      let sleeping = jobs.map(|j| j.is_sleeping()).unwrap_or(false);
+      need.rest = if sleeping {
+        (need.rest + REST_RECOVERY * dt).min(1.0)
+      } else {
+        (need.rest - REST_DECAY * fatigue * dt).max(0.0)
+      };
+    }
+  }
+}
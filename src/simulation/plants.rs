@@ -0,0 +1,423 @@
+use std::collections::HashMap;
+use amethyst::{
+  core::{ nalgebra::{ Vector2, Vector3 }, transform::Transform },
+  ecs::{ Builder, Component, DenseVecStorage, Entities, Entity, Join, Read, ReadStorage, System, World, WriteStorage },
+  renderer::{ SpriteRender, SpriteSheetHandle },
+};
+use serde_derive::{ Serialize, Deserialize };
+use super::{ SimTime, Clock, Season, Temperature, Movement, Job, Jobs, Needs };
+use super::clock::{ TICKS_PER_MINUTE, MINUTES_PER_DAY };
+use crate::asset_loader::{ load_png_texture, load_ss_asset };
+use crate::rendering::tile_map::{ Tile, tile_center };
+use crate::resource::{ Resource, ResourceStack };
+
+// plants do not grow in colder weather
+const GROWTH_MIN_TEMPERATURE: f32 = 5.0;
+
+// hunger a single fruit takes away
+const FRUIT_NOURISHMENT: f32 = 0.2;
+
+const PLANT_SCALE: f32 = 0.6;
+// sprites are 64 pixels high
+const PLANT_HEIGHT: f32 = 64.0;
+
+fn allows_growth(temperature: f32) -> bool {
+  temperature >= GROWTH_MIN_TEMPERATURE
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PlantKind {
+  BerryBush,
+  AppleTree,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum GrowthStage {
+  Sprout,
+  Young,
+  Mature,
+}
+
+impl GrowthStage {
+  fn next(&self) -> GrowthStage {
+    match self {
+      GrowthStage::Sprout => GrowthStage::Young,
+      GrowthStage::Young | GrowthStage::Mature => GrowthStage::Mature,
+    }
+  }
+}
+
+impl PlantKind {
+  pub fn days_per_stage(&self) -> u32 {
+    match self {
+      PlantKind::BerryBush => 2,
+      PlantKind::AppleTree => 6,
+    }
+  }
+
+  pub fn max_fruit(&self) -> u32 {
+    match self {
+      PlantKind::BerryBush => 4,
+      PlantKind::AppleTree => 10,
+    }
+  }
+
+  // in-game minutes a new fruit takes to grow back
+  pub fn regrowth(&self) -> u32 {
+    match self {
+      PlantKind::BerryBush => 4 * 60,
+      PlantKind::AppleTree => 3 * 60,
+    }
+  }
+
+  pub fn bears_fruit_in(&self, season: Season) -> bool {
+    match (self, season) {
+      (PlantKind::BerryBush, Season::Summer) => true,
+      (PlantKind::AppleTree, Season::Summer) | (PlantKind::AppleTree, Season::Autumn) => true,
+      _ => false,
+    }
+  }
+
+  pub fn fruit(&self) -> Resource {
+    Resource::Apple
+  }
+}
+
+pub struct Plant {
+  pub kind: PlantKind,
+  pub stage: GrowthStage,
+  // minutes grown in the current stage
+  pub growth: u32,
+  pub fruit: u32,
+  // minutes until the next fruit
+  pub regrowth: u32,
+}
+
+impl Plant {
+  pub fn new(kind: PlantKind, stage: GrowthStage) -> Self {
+    Plant {
+      kind,
+      stage,
+      growth: 0,
+      fruit: 0,
+      regrowth: kind.regrowth(),
+    }
+  }
+
+  // every kind has sprout, young, mature and fruiting sprites in a row
+  fn sprite_number(&self) -> usize {
+    let first = match self.kind {
+      PlantKind::BerryBush => 0,
+      PlantKind::AppleTree => 4,
+    };
+    match self.stage {
+      GrowthStage::Sprout => first,
+      GrowthStage::Young => first + 1,
+      GrowthStage::Mature if self.fruit == 0 => first + 2,
+      GrowthStage::Mature => first + 3,
+    }
+  }
+}
+
+impl Component for Plant {
+  type Storage = DenseVecStorage<Self>;
+}
+
+pub struct PlantSprites {
+  pub sprite_sheet: SpriteSheetHandle,
+}
+
+pub fn load_plant_sprites(world: &mut World) {
+  let texture = load_png_texture(world, "resources/sprites/plants/plants.png".to_string());
+  let sprite_sheet = load_ss_asset(world, "resources/sprites/plants/sprite_sheet.ron".to_string(), texture);
+  world.add_resource(PlantSprites { sprite_sheet });
+}
+
+// plant grows from the tile center, below fires and pawns
+fn plant_transform(at: Vector2<f32>) -> Transform {
+  let mut transform: Transform = Default::default();
+  transform.set_scale(PLANT_SCALE, PLANT_SCALE, 1.0);
+  transform.set_xyz(at.x, at.y + PLANT_HEIGHT * PLANT_SCALE / 2.0, 0.7);
+  transform
+}
+
+pub fn place_plant(world: &mut World, kind: PlantKind, stage: GrowthStage, tile: Tile) {
+  world.register::<Plant>();
+  world.register::<Tile>();
+  let plant = Plant::new(kind, stage);
+  let sprite = world.res.try_fetch::<PlantSprites>().map(|sprites| SpriteRender {
+    sprite_sheet: sprites.sprite_sheet.clone(),
+    sprite_number: plant.sprite_number(),
+  });
+  let at = tile_center(world, &tile);
+  let entity = world.create_entity()
+    .with(plant)
+    .with(tile);
+  match (sprite, at) {
+    (Some(s), Some(at)) => entity.with(s).with(plant_transform(at)).build(),
+    _ => entity.build()
+  };
+}
+
+// Plants grow only while it is warm enough. Fruit comes in the season of the kind,
+// whatever was not picked withers when the season is over.
+pub struct PlantGrowthSystem;
+
+impl<'a> System<'a> for PlantGrowthSystem {
+  type SystemData = (
+    Read<'a, SimTime>,
+    Read<'a, Clock>,
+    Read<'a, Temperature>,
+    ReadStorage<'a, Tile>,
+    WriteStorage<'a, Plant>,
+    WriteStorage<'a, SpriteRender>,
+  );
+
+  fn run(&mut self, (time, clock, temperature, tiles, mut plants, mut sprites): Self::SystemData) {
+    // picked fruit shows right away, not at the next minute
+    for (plant, sprite) in (&plants, &mut sprites).join() {
+      if sprite.sprite_number != plant.sprite_number() {
+        sprite.sprite_number = plant.sprite_number();
+      }
+    }
+    if time.tick % TICKS_PER_MINUTE != 0 {
+      return;
+    }
+    for (tile, plant) in (&tiles, &mut plants).join() {
+      if !plant.kind.bears_fruit_in(clock.season) {
+        plant.fruit = 0;
+      }
+      if !temperature.at(tile).map(allows_growth).unwrap_or(false) {
+        continue;
+      }
+      if plant.stage != GrowthStage::Mature {
+        plant.growth += 1;
+        if plant.growth >= plant.kind.days_per_stage() * MINUTES_PER_DAY {
+          plant.growth = 0;
+          plant.stage = plant.stage.next();
+        }
+        continue;
+      }
+      if !plant.kind.bears_fruit_in(clock.season) || plant.fruit >= plant.kind.max_fruit() {
+        continue;
+      }
+      plant.regrowth = plant.regrowth.saturating_sub(1);
+      if plant.regrowth == 0 {
+        plant.fruit += 1;
+        plant.regrowth = plant.kind.regrowth();
+      }
+    }
+  }
+}
+
+// Pawns which reached the plant they were sent to pick all its fruit,
+// eat until they are full and leave the rest in a stack on the tile.
+pub struct ForageSystem;
+
+impl<'a> System<'a> for ForageSystem {
+  type SystemData = (
+    Entities<'a>,
+    ReadStorage<'a, Movement>,
+    WriteStorage<'a, Jobs>,
+    WriteStorage<'a, Needs>,
+    WriteStorage<'a, Plant>,
+    WriteStorage<'a, ResourceStack>,
+    WriteStorage<'a, Tile>,
+  );
+
+  fn run(&mut self, (entities, movements, mut jobs, mut needs, mut plants, mut stacks, mut tiles): Self::SystemData) {
+    let arrived: Vec<(Entity, Vector3<i32>)> = (&entities, &tiles, &movements, &jobs).join()
+      .filter(|(_, _, movement, _)| movement.is_idle())
+      .filter_map(|(e, tile, _, jobs)| match &jobs.current {
+        Some(Job::Forage(target)) if target.position == tile.position => Some((e, tile.position)),
+        _ => None
+      })
+      .collect();
+    if arrived.is_empty() {
+      return;
+    }
+    let mut picked: HashMap<Vector3<i32>, (Resource, u32)> = HashMap::new();
+    for (pawn, position) in arrived {
+      let mut need = needs.get_mut(pawn);
+      for (tile, plant) in (&tiles, &mut plants).join().filter(|(t, _)| t.position == position) {
+        if let Some(n) = need.as_mut() {
+          while plant.fruit > 0 && n.hunger < 1.0 {
+            plant.fruit -= 1;
+            n.hunger = (n.hunger + FRUIT_NOURISHMENT).min(1.0);
+          }
+        }
+        if plant.fruit > 0 {
+          let entry = picked.entry(tile.position).or_insert((plant.kind.fruit(), 0));
+          entry.1 += plant.fruit;
+          plant.fruit = 0;
+        }
+      }
+      if let Some(j) = jobs.get_mut(pawn) {
+        j.current = None;
+      }
+    }
+    // picked fruit goes on top of the stack already lying there
+    let mut sorted: Vec<_> = picked.into_iter().collect();
+    sorted.sort_by_key(|(p, _)| (p.x, p.y));
+    for (position, (resource, count)) in sorted {
+      let existing = (&mut stacks, &tiles).join()
+        .find(|(s, t)| s.resource == resource && t.position == position)
+        .map(|(s, _)| s);
+      match existing {
+        Some(stack) => stack.count += count,
+        None => {
+          let e = entities.create();
+          let results = [
+            stacks.insert(e, ResourceStack { resource, count }).map(|_| ()),
+            tiles.insert(e, Tile { position }).map(|_| ()),
+          ];
+          for r in results.iter() {
+            if let Err(err) = r {
+              println!("cannot put picked fruit: {}", err);
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use amethyst::ecs::RunNow;
+  use super::*;
+
+  fn plant_world(season: Season, temperature: f32) -> World {
+    let mut world = World::new();
+    world.register::<Tile>();
+    world.register::<Plant>();
+    world.register::<SpriteRender>();
+    world.register::<Movement>();
+    world.register::<Jobs>();
+    world.register::<Needs>();
+    world.register::<ResourceStack>();
+    world.add_resource(SimTime::default());
+    world.add_resource(Clock { season, ..Clock::default() });
+    let mut field = Temperature::default();
+    field.tiles.insert(Tile::new(0, 0).position, temperature);
+    world.add_resource(field);
+    world
+  }
+
+  fn plant(world: &mut World, kind: PlantKind, stage: GrowthStage, fruit: u32) -> Entity {
+    world.create_entity()
+      .with(Plant { fruit, ..Plant::new(kind, stage) })
+      .with(Tile::new(0, 0))
+      .build()
+  }
+
+  fn grow(world: &mut World, minutes: u32) {
+    for _ in 0..minutes {
+      world.write_resource::<SimTime>().tick += TICKS_PER_MINUTE;
+      PlantGrowthSystem.run_now(&world.res);
+    }
+  }
+
+  fn stage_and_fruit(world: &World, plant: Entity) -> (GrowthStage, u32) {
+    let plants = world.read_storage::<Plant>();
+    let p = plants.get(plant).unwrap();
+    (p.stage, p.fruit)
+  }
+
+  #[test]
+  fn sprout_grows_stage_by_stage() {
+    let mut world = plant_world(Season::Spring, 12.0);
+    let bush = plant(&mut world, PlantKind::BerryBush, GrowthStage::Sprout, 0);
+    let stage = PlantKind::BerryBush.days_per_stage() * MINUTES_PER_DAY;
+    grow(&mut world, stage - 1);
+    assert_eq!(stage_and_fruit(&world, bush), (GrowthStage::Sprout, 0));
+    grow(&mut world, 1);
+    assert_eq!(stage_and_fruit(&world, bush), (GrowthStage::Young, 0));
+    grow(&mut world, stage);
+    assert_eq!(stage_and_fruit(&world, bush), (GrowthStage::Mature, 0));
+  }
+
+  #[test]
+  fn cold_stops_growth_and_fruit() {
+    let mut world = plant_world(Season::Summer, GROWTH_MIN_TEMPERATURE - 1.0);
+    let sprout = plant(&mut world, PlantKind::BerryBush, GrowthStage::Sprout, 0);
+    let bush = plant(&mut world, PlantKind::BerryBush, GrowthStage::Mature, 0);
+    grow(&mut world, PlantKind::BerryBush.days_per_stage() * MINUTES_PER_DAY);
+    assert_eq!(stage_and_fruit(&world, sprout), (GrowthStage::Sprout, 0));
+    assert_eq!(stage_and_fruit(&world, bush), (GrowthStage::Mature, 0));
+  }
+
+  #[test]
+  fn fruit_grows_in_season_and_withers_after() {
+    let mut world = plant_world(Season::Summer, 22.0);
+    let bush = plant(&mut world, PlantKind::BerryBush, GrowthStage::Mature, 0);
+    grow(&mut world, PlantKind::BerryBush.regrowth());
+    assert_eq!(stage_and_fruit(&world, bush), (GrowthStage::Mature, 1));
+    grow(&mut world, PlantKind::BerryBush.regrowth() * 10);
+    assert_eq!(stage_and_fruit(&world, bush), (GrowthStage::Mature, PlantKind::BerryBush.max_fruit()));
+
+    world.write_resource::<Clock>().season = Season::Autumn;
+    grow(&mut world, 1);
+    assert_eq!(stage_and_fruit(&world, bush), (GrowthStage::Mature, 0));
+  }
+
+  #[test]
+  fn sprite_follows_stage_and_fruit() {
+    let numbers: Vec<usize> = [
+      (GrowthStage::Sprout, 0),
+      (GrowthStage::Young, 0),
+      (GrowthStage::Mature, 0),
+      (GrowthStage::Mature, 2),
+    ].iter()
+      .map(|(stage, fruit)| Plant { fruit: *fruit, ..Plant::new(PlantKind::AppleTree, *stage) }.sprite_number())
+      .collect();
+    assert_eq!(numbers, vec![4, 5, 6, 7]);
+  }
+
+  fn forager(world: &mut World, hunger: f32) -> Entity {
+    let mut jobs = Jobs::default();
+    jobs.current = Some(Job::Forage(Tile::new(0, 0)));
+    world.create_entity()
+      .with(Tile::new(0, 0))
+      .with(Movement::default())
+      .with(jobs)
+      .with(Needs { hunger, ..Needs::default() })
+      .build()
+  }
+
+  fn stacks(world: &World) -> Vec<u32> {
+    world.read_storage::<ResourceStack>().join()
+      .filter(|s| s.resource == Resource::Apple)
+      .map(|s| s.count)
+      .collect()
+  }
+
+  #[test]
+  fn hungry_forager_eats_and_stacks_the_rest() {
+    let mut world = plant_world(Season::Summer, 22.0);
+    let bush = plant(&mut world, PlantKind::BerryBush, GrowthStage::Mature, 4);
+    let pawn = forager(&mut world, 0.5);
+    world.create_entity()
+      .with(ResourceStack { resource: Resource::Apple, count: 2 })
+      .with(Tile::new(0, 0))
+      .build();
+    ForageSystem.run_now(&world.res);
+    world.maintain();
+
+    // three fruit fill the pawn up, the last one goes on the stack lying there
+    assert_eq!(world.read_storage::<Needs>().get(pawn).unwrap().hunger, 1.0);
+    assert_eq!(stage_and_fruit(&world, bush), (GrowthStage::Mature, 0));
+    assert_eq!(stacks(&world), vec![3]);
+    assert!(world.read_storage::<Jobs>().get(pawn).unwrap().current.is_none());
+  }
+
+  #[test]
+  fn full_forager_leaves_a_new_stack() {
+    let mut world = plant_world(Season::Summer, 22.0);
+    plant(&mut world, PlantKind::BerryBush, GrowthStage::Mature, 4);
+    forager(&mut world, 1.0);
+    ForageSystem.run_now(&world.res);
+    world.maintain();
+    assert_eq!(stacks(&world), vec![4]);
+  }
+}
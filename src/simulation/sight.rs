@@ -0,0 +1,287 @@
+use std::collections::{ HashMap, HashSet };
+use amethyst::{
+  core::nalgebra::Vector3,
+  ecs::{ Join, Read, ReadStorage, System, Write },
+};
+use crate::game_state::TileMapTilesKind;
+use crate::pawn::Faction;
+use super::WeatherState;
+use crate::rendering::tile_map::{ Tile, TileMap, GridTopology, Elevation };
+
+// eyes are this many elevation steps above the ground
+pub const EYE_HEIGHT: f32 = 1.0;
+// tiles a pawn sees around itself in clear weather, never less than the minimum
+pub const SIGHT_RADIUS: i32 = 6;
+const MIN_SIGHT_RADIUS: i32 = 2;
+
+// Tiles between the two are checked against the straight line from eyes to the target ground,
+// anything higher blocks the view. Tiles out of the map do not block.
+pub fn line_of_sight(
+  topology: GridTopology,
+  from: &Tile,
+  to: &Tile,
+  elevation_of: &Fn(&Tile) -> Option<i32>
+) -> bool {
+  let line = topology.line(from, to);
+  if line.len() <= 2 {
+    return true;
+  }
+  let eye = elevation_of(from).unwrap_or(0) as f32 + EYE_HEIGHT;
+  let target = elevation_of(to).unwrap_or(0) as f32;
+  let steps = (line.len() - 1) as f32;
+  line.iter()
+    .enumerate()
+    .skip(1)
+    .take(line.len() - 2)
+    .all(|(i, tile)| {
+      let sight = eye + (target - eye) * i as f32 / steps;
+      elevation_of(tile).map(|e| e as f32 <= sight).unwrap_or(true)
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TileVisibility {
+  Unexplored,
+  Explored,
+  Visible,
+}
+
+#[derive(Default)]
+pub struct FactionVision {
+  pub visible: HashSet<Vector3<i32>>,
+  pub explored: HashSet<Vector3<i32>>,
+  // tiles the vision was computed from, sorted
+  observers: Vec<Vector3<i32>>,
+}
+
+// Fog of war. Factions without any vision computed, like in the map editor, see everything.
+#[derive(Default)]
+pub struct Visibility {
+  pub factions: HashMap<Faction, FactionVision>,
+  pub revealed: bool,
+  // radius the vision was computed with
+  radius: i32,
+}
+
+impl Visibility {
+  pub fn of(&self, faction: Faction, tile: &Tile) -> TileVisibility {
+    let vision = match self.factions.get(&faction) {
+      Some(v) if !self.revealed => v,
+      _ => return TileVisibility::Visible
+    };
+    if vision.visible.contains(&tile.position) {
+      TileVisibility::Visible
+    } else if vision.explored.contains(&tile.position) {
+      TileVisibility::Explored
+    } else {
+      TileVisibility::Unexplored
+    }
+  }
+}
+
+// recomputes field of view of a faction only when some of its pawns changed tiles
+pub struct VisionSystem;
+
+impl<'a> System<'a> for VisionSystem {
+  type SystemData = (
+    Write<'a, Visibility>,
+    Read<'a, WeatherState>,
+    ReadStorage<'a, TileMap>,
+    ReadStorage<'a, Tile>,
+    ReadStorage<'a, TileMapTilesKind>,
+    ReadStorage<'a, Elevation>,
+    ReadStorage<'a, Faction>,
+  );
+
+  fn run(&mut self, (mut visibility, weather, maps, tiles, kinds, elevations, factions): Self::SystemData) {
+    let topology = match (&maps).join().next() {
+      None => return,
+      Some(tm) => tm.topology
+    };
+    let radius = (SIGHT_RADIUS - weather.current.sight_penalty()).max(MIN_SIGHT_RADIUS);
+    if radius != visibility.radius {
+      visibility.radius = radius;
+      for vision in visibility.factions.values_mut() {
+        vision.observers.clear();
+      }
+    }
+    // factions left without pawns see nothing, but remember what they explored
+    let mut observers: HashMap<Faction, Vec<Vector3<i32>>> = visibility.factions.keys()
+      .map(|f| (*f, Vec::new()))
+      .collect();
+    for (tile, faction) in (&tiles, &factions).join() {
+      observers.entry(*faction).or_insert_with(Vec::new).push(tile.position);
+    }
+    for positions in observers.values_mut() {
+      positions.sort_by_key(|p| (p.x, p.y));
+      positions.dedup();
+    }
+    let changed = observers.iter()
+      .any(|(f, o)| visibility.factions.get(f).map(|v| &v.observers != o).unwrap_or(true));
+    if !changed {
+      return;
+    }
+
+    let map: HashMap<Vector3<i32>, i32> = (&tiles, &kinds, (&elevations).maybe()).join()
+      .map(|(t, _, e)| (t.position, e.map(|e| e.0).unwrap_or(0)))
+      .collect();
+    let elevation_of = |t: &Tile| map.get(&t.position).cloned();
+    for (faction, positions) in observers {
+      let vision = visibility.factions.entry(faction).or_insert_with(FactionVision::default);
+      if vision.observers == positions {
+        continue;
+      }
+      vision.visible.clear();
+      for position in positions.iter() {
+        let from = Tile::new(position.x, position.y);
+        for tile in topology.area(&from, radius) {
+          if map.contains_key(&tile.position) && line_of_sight(topology, &from, &tile, &elevation_of) {
+            vision.visible.insert(tile.position);
+          }
+        }
+      }
+      vision.explored.extend(vision.visible.iter().cloned());
+      vision.observers = positions;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+  use amethyst::{
+    core::nalgebra::Vector2,
+    ecs::{ Builder, Entity, RunNow, World },
+  };
+  use crate::simulation::Weather;
+  use super::*;
+
+  fn heights(raised: &[((i32, i32), i32)]) -> HashMap<(i32, i32), i32> {
+    let mut map = HashMap::new();
+    for x in -5..=5 {
+      for y in -5..=5 {
+        map.insert((x, y), 0);
+      }
+    }
+    for (at, e) in raised.iter() {
+      map.insert(*at, *e);
+    }
+    map
+  }
+
+  fn sees(map: &HashMap<(i32, i32), i32>, from: (i32, i32), to: (i32, i32)) -> bool {
+    let elevation_of = |t: &Tile| map.get(&(t.position.x, t.position.y)).cloned();
+    line_of_sight(GridTopology::Square4, &Tile::new(from.0, from.1), &Tile::new(to.0, to.1), &elevation_of)
+  }
+
+  #[test]
+  fn flat_ground_is_visible() {
+    assert!(sees(&heights(&[]), (0, 0), (3, 0)));
+  }
+
+  #[test]
+  fn neighbours_are_always_visible() {
+    let map = heights(&[((1, 0), 10), ((0, 0), -10)]);
+    assert!(sees(&map, (0, 0), (1, 0)));
+    assert!(sees(&map, (0, 0), (0, 0)));
+  }
+
+  #[test]
+  fn wall_blocks_the_view() {
+    let map = heights(&[((1, 0), 3)]);
+    assert!(!sees(&map, (0, 0), (3, 0)));
+    assert!(!sees(&map, (3, 0), (0, 0)));
+  }
+
+  #[test]
+  fn hill_sees_over_a_bump() {
+    let map = heights(&[((0, 0), 3), ((1, 0), 2)]);
+    assert!(sees(&map, (0, 0), (3, 0)));
+    // looking up the slope clears it too
+    assert!(sees(&map, (3, 0), (0, 0)));
+  }
+
+  #[test]
+  fn tiles_off_the_map_do_not_block() {
+    let elevation_of = |t: &Tile| if t.position.x == 1 { None } else { Some(0) };
+    assert!(line_of_sight(GridTopology::Square4, &Tile::new(0, 0), &Tile::new(3, 0), &elevation_of));
+  }
+
+  fn vision_world() -> World {
+    let mut world = World::new();
+    world.register::<TileMap>();
+    world.register::<Tile>();
+    world.register::<TileMapTilesKind>();
+    world.register::<Elevation>();
+    world.register::<Faction>();
+    world.add_resource(Visibility::default());
+    world.add_resource(WeatherState::default());
+    world.create_entity()
+      .with(TileMap::new(GridTopology::Square4, Vector2::new(1.0, 1.0)))
+      .build();
+    for x in -20..=20 {
+      for y in -2..=2 {
+        world.create_entity()
+          .with(Tile::new(x, y))
+          .with(TileMapTilesKind::Grass)
+          .build();
+      }
+    }
+    world
+  }
+
+  fn observer(world: &mut World, at: (i32, i32), faction: Faction) -> Entity {
+    world.create_entity()
+      .with(Tile::new(at.0, at.1))
+      .with(faction)
+      .build()
+  }
+
+  fn seen(world: &World, faction: Faction, at: (i32, i32)) -> TileVisibility {
+    world.read_resource::<Visibility>().of(faction, &Tile::new(at.0, at.1))
+  }
+
+  #[test]
+  fn vision_follows_observers_and_keeps_explored_tiles() {
+    let mut world = vision_world();
+    let pawn = observer(&mut world, (-10, 0), Faction::Colony);
+    VisionSystem.run_now(&world.res);
+    assert_eq!(seen(&world, Faction::Colony, (-10, 0)), TileVisibility::Visible);
+    assert_eq!(seen(&world, Faction::Colony, (10, 0)), TileVisibility::Unexplored);
+
+    world.write_storage::<Tile>().insert(pawn, Tile::new(10, 0)).unwrap();
+    VisionSystem.run_now(&world.res);
+    assert_eq!(seen(&world, Faction::Colony, (10, 0)), TileVisibility::Visible);
+    assert_eq!(seen(&world, Faction::Colony, (-10, 0)), TileVisibility::Explored);
+  }
+
+  #[test]
+  fn factions_see_apart_until_revealed() {
+    let mut world = vision_world();
+    observer(&mut world, (-10, 0), Faction::Colony);
+    observer(&mut world, (10, 0), Faction::Wild);
+    VisionSystem.run_now(&world.res);
+    assert_eq!(seen(&world, Faction::Colony, (10, 0)), TileVisibility::Unexplored);
+    assert_eq!(seen(&world, Faction::Wild, (10, 0)), TileVisibility::Visible);
+    assert_eq!(seen(&world, Faction::Wild, (-10, 0)), TileVisibility::Unexplored);
+
+    world.write_resource::<Visibility>().revealed = true;
+    assert_eq!(seen(&world, Faction::Colony, (10, 0)), TileVisibility::Visible);
+  }
+
+  #[test]
+  fn bad_weather_shortens_sight() {
+    let mut world = vision_world();
+    observer(&mut world, (0, 0), Faction::Colony);
+    VisionSystem.run_now(&world.res);
+    assert_eq!(seen(&world, Faction::Colony, (SIGHT_RADIUS, 0)), TileVisibility::Visible);
+
+    // vision is recomputed for the new weather even though nobody moved
+    world.write_resource::<WeatherState>().current = Weather::Storm;
+    VisionSystem.run_now(&world.res);
+    let radius = SIGHT_RADIUS - Weather::Storm.sight_penalty();
+    assert!(radius >= MIN_SIGHT_RADIUS);
+    assert_eq!(seen(&world, Faction::Colony, (radius, 0)), TileVisibility::Visible);
+    assert_eq!(seen(&world, Faction::Colony, (radius + 1, 0)), TileVisibility::Explored);
+  }
+}
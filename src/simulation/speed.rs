@@ -0,0 +1,79 @@
+use amethyst::ecs::{ Read, System, Write };
+use winit::VirtualKeyCode;
+use crate::console::Console;
+use crate::input::InputState;
+use crate::states::PauseMenu;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameSpeed {
+  Paused,
+  Normal,
+  Fast,
+  Fastest,
+}
+
+impl Default for GameSpeed {
+  fn default() -> Self {
+    GameSpeed::Normal
+  }
+}
+
+impl GameSpeed {
+  pub fn multiplier(&self) -> u32 {
+    match self {
+      GameSpeed::Paused => 0,
+      GameSpeed::Normal => 1,
+      GameSpeed::Fast => 2,
+      GameSpeed::Fastest => 3,
+    }
+  }
+
+  pub fn index(&self) -> usize {
+    self.multiplier() as usize
+  }
+}
+
+pub struct GameSpeedSystem {
+  // speed to return to when pause is toggled off
+  resume: GameSpeed,
+}
+
+impl GameSpeedSystem {
+  pub fn new() -> Self {
+    GameSpeedSystem {
+      resume: GameSpeed::Normal
+    }
+  }
+}
+
+impl<'a> System<'a> for GameSpeedSystem {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PauseMenu>,
+    Read<'a, Console>,
+    Write<'a, GameSpeed>,
+  );
+
+  fn run(&mut self, (input, pause_menu, console, mut speed): Self::SystemData) {
+    if pause_menu.open || console.open {
+      return;
+    }
+    if input.is_key_down(VirtualKeyCode::Space) {
+      if *speed == GameSpeed::Paused {
+        *speed = self.resume;
+      } else {
+        self.resume = *speed;
+        *speed = GameSpeed::Paused;
+      }
+    }
+    if input.is_key_down(VirtualKeyCode::Key1) {
+      *speed = GameSpeed::Normal;
+    }
+    if input.is_key_down(VirtualKeyCode::Key2) {
+      *speed = GameSpeed::Fast;
+    }
+    if input.is_key_down(VirtualKeyCode::Key3) {
+      *speed = GameSpeed::Fastest;
+    }
+  }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use amethyst::{
+  core::nalgebra::Vector3,
+  ecs::{ Entities, Entity, Join, Read, ReadStorage, System, Write, WriteStorage },
+};
+use super::{ SimTime, Clock, Season, Fire, Needs };
+use super::clock::MINUTES_PER_DAY;
+use crate::game_state::TileMapTilesKind;
+use crate::rendering::tile_map::{ Tile, TileMap, TileSprite, Elevation, tile_sprite };
+
+// degrees of Celsius
+const DAILY_SWING: f32 = 5.0;
+// warmest minute of the day
+const WARMEST_AT: u32 = 15 * 60;
+const PER_ELEVATION: f32 = -2.0;
+// water freezes and thaws a bit apart, so the ice does not flicker around zero
+const FREEZE_BELOW: f32 = -2.0;
+const THAW_ABOVE: f32 = 1.0;
+// pawns do not get cold above this
+pub const COMFORT_TEMPERATURE: f32 = 15.0;
+
+fn season_average(season: Season) -> f32 {
+  match season {
+    Season::Spring => 12.0,
+    Season::Summer => 22.0,
+    Season::Autumn => 9.0,
+    Season::Winter => -8.0,
+  }
+}
+
+// water keeps its surroundings milder, bare dirt heats up faster
+fn biome_offset(kind: TileMapTilesKind) -> f32 {
+  match kind {
+    TileMapTilesKind::Water | TileMapTilesKind::Ice => 1.0,
+    TileMapTilesKind::Dirt => 0.5,
+    TileMapTilesKind::Grass => 0.0,
+  }
+}
+
+// air temperature of the open map, before biome, elevation and fires
+pub fn weather_temperature(clock: &Clock) -> f32 {
+  let day = (clock.minute as f32 - WARMEST_AT as f32) / MINUTES_PER_DAY as f32;
+  season_average(clock.season) + DAILY_SWING * (2.0 * PI * day).cos()
+}
+
+#[derive(Default)]
+pub struct Temperature {
+  pub tiles: HashMap<Vector3<i32>, f32>,
+  // minute of the clock the field was computed for
+  computed_at: Option<(u32, u32)>,
+}
+
+impl Temperature {
+  pub fn at(&self, tile: &Tile) -> Option<f32> {
+    self.tiles.get(&tile.position).cloned()
+  }
+}
+
+// clock moves by whole minutes, the field is recomputed once per minute
+pub struct TemperatureSystem;
+
+impl<'a> System<'a> for TemperatureSystem {
+  type SystemData = (
+    Read<'a, Clock>,
+    Write<'a, Temperature>,
+    ReadStorage<'a, TileMap>,
+    ReadStorage<'a, Tile>,
+    ReadStorage<'a, TileMapTilesKind>,
+    ReadStorage<'a, Elevation>,
+    ReadStorage<'a, Fire>,
+  );
+
+  fn run(&mut self, (clock, mut temperature, maps, tiles, kinds, elevations, fires): Self::SystemData) {
+    let now = (clock.day, clock.minute);
+    if temperature.computed_at == Some(now) {
+      return;
+    }
+    temperature.computed_at = Some(now);
+    let topology = (&maps).join().next().map(|tm| tm.topology).unwrap_or_default();
+    let air = weather_temperature(&clock);
+    temperature.tiles = (&tiles, &kinds, (&elevations).maybe()).join()
+      .map(|(t, kind, e)| {
+        let elevation = e.map(|e| e.0).unwrap_or(0);
+        (t.position, air + biome_offset(*kind) + PER_ELEVATION * elevation as f32)
+      })
+      .collect();
+    for (at, fire) in (&tiles, &fires).join() {
+      let (heat, radius) = fire.heat();
+      for tile in topology.area(at, radius) {
+        let falloff = 1.0 - topology.distance(at, &tile) as f32 / (radius + 1) as f32;
+        if let Some(t) = temperature.tiles.get_mut(&tile.position) {
+          *t += heat * falloff;
+        }
+      }
+    }
+  }
+}
+
+// water tiles turn into ice in frost and back when it gets warm
+pub struct FreezeSystem;
+
+impl<'a> System<'a> for FreezeSystem {
+  type SystemData = (
+    Entities<'a>,
+    Read<'a, Temperature>,
+    ReadStorage<'a, Tile>,
+    WriteStorage<'a, TileMapTilesKind>,
+    WriteStorage<'a, TileSprite>,
+  );
+
+  fn run(&mut self, (entities, temperature, tiles, mut kinds, mut sprites): Self::SystemData) {
+    // kinds are flagged, only the tiles which really change are written
+    let changes: Vec<(Entity, TileMapTilesKind)> = (&entities, &tiles, &kinds).join()
+      .filter_map(|(e, tile, kind)| {
+        let t = temperature.at(tile)?;
+        match *kind {
+          TileMapTilesKind::Water if t < FREEZE_BELOW => Some((e, TileMapTilesKind::Ice)),
+          TileMapTilesKind::Ice if t > THAW_ABOVE => Some((e, TileMapTilesKind::Water)),
+          _ => None,
+        }
+      })
+      .collect();
+    for (e, changed) in changes {
+      if let Some(kind) = kinds.get_mut(e) {
+        *kind = changed;
+      }
+      if let Some(sprite) = sprites.get_mut(e) {
+        *sprite = tile_sprite(changed);
+      }
+    }
+  }
+}
+
+// per in-game second and degree away from comfort
+const CHILL_RATE: f32 = 1.0 / 6000.0;
+const WARM_RATE: f32 = 1.0 / 1500.0;
+
+pub struct WarmthSystem;
+
+impl<'a> System<'a> for WarmthSystem {
+  type SystemData = (
+    Read<'a, SimTime>,
+    Read<'a, Temperature>,
+    ReadStorage<'a, Tile>,
+    WriteStorage<'a, Needs>,
+  );
+
+  fn run(&mut self, (time, temperature, tiles, mut needs): Self::SystemData) {
+    let dt = time.tick_seconds;
+    for (tile, need) in (&tiles, &mut needs).join() {
+      let t = match temperature.at(tile) {
+        None => continue,
+        Some(t) => t
+      };
+      let change = if t >= COMFORT_TEMPERATURE {
+        WARM_RATE * (t - COMFORT_TEMPERATURE + 1.0)
+      } else {
+        -CHILL_RATE * (COMFORT_TEMPERATURE - t)
+      };
+      need.warmth = (need.warmth + change * dt).max(0.0).min(1.0);
+    }
+  }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use amethyst::{
+  ecs::{ Component, DenseVecStorage, Entity, Entities, Join, ReadStorage, System, WriteStorage, Resources },
+};
+use super::{ Movement, Job, Jobs, Needs };
+use super::ai::{ Terrain, build_terrain, can_step, step_towards };
+use super::combat::{ Health, attack_target, REACH };
+use crate::game_state::TileMapTilesKind;
+use crate::pawn::{ Spece, PawnId };
+use crate::random::{ GameSeed, Rng };
+use crate::resource::{ Resource, ResourceStack };
+use crate::rendering::tile_map::{ Tile, TileMap, GridTopology, Elevation };
+
+// wolves of a pack share the den, the territory is around it
+#[derive(Clone, Debug)]
+pub struct Pack {
+  pub leader: PawnId,
+  pub den: Tile,
+}
+
+impl Component for Pack {
+  type Storage = DenseVecStorage<Self>;
+}
+
+pub const TERRITORY_RADIUS: i32 = 5;
+// lone wolves join a pack this close
+const JOIN_RANGE: i32 = 4;
+// intruders are noticed this close
+const HUNT_RANGE: i32 = 4;
+// prey is not followed farther from the den
+const CHASE_RANGE: i32 = TERRITORY_RADIUS + 3;
+// packs this big attack intruders even when not hungry
+const PACK_COURAGE: usize = 3;
+const HUNT_HUNGER: f32 = 0.5;
+const FLEE_HEALTH: f32 = 0.35;
+const FLEE_RANGE: i32 = 5;
+const WANDER_CHANCE: f32 = 0.02;
+const MEAT_NOURISHMENT: f32 = 0.5;
+
+pub struct WildlifeSystem {
+  rng: Rng,
+}
+
+impl WildlifeSystem {
+  pub fn new() -> Self {
+    WildlifeSystem {
+      rng: Rng::new(0),
+    }
+  }
+
+  fn wander(&mut self, topology: GridTopology, map: &Terrain, tile: &Tile, den: &Tile) -> Option<Tile> {
+    if topology.distance(tile, den) > TERRITORY_RADIUS {
+      return step_towards(topology, map, tile, den, false);
+    }
+    if !self.rng.chance(WANDER_CHANCE) {
+      return None;
+    }
+    let options: Vec<Tile> = topology.neighbours(tile)
+      .into_iter()
+      .filter(|n| can_step(map, tile, n) && topology.distance(n, den) <= TERRITORY_RADIUS)
+      .collect();
+    if options.is_empty() {
+      return None;
+    }
+    Some(options[self.rng.range(0, options.len() as i32) as usize].clone())
+  }
+}
+
+fn nearest<'t>(topology: GridTopology, from: &Tile, among: &'t [(PawnId, Tile)], range: i32) -> Option<&'t (PawnId, Tile)> {
+  among.iter()
+    .filter(|(_, t)| topology.distance(from, t) <= range)
+    .min_by_key(|(id, t)| (topology.distance(from, t), id.0))
+}
+
+impl<'a> System<'a> for WildlifeSystem {
+  type SystemData = (
+    Entities<'a>,
+    ReadStorage<'a, Spece>,
+    ReadStorage<'a, PawnId>,
+    ReadStorage<'a, Tile>,
+    ReadStorage<'a, TileMapTilesKind>,
+    ReadStorage<'a, Elevation>,
+    ReadStorage<'a, TileMap>,
+    ReadStorage<'a, Health>,
+    WriteStorage<'a, Pack>,
+    WriteStorage<'a, Needs>,
+    WriteStorage<'a, ResourceStack>,
+    WriteStorage<'a, Movement>,
+    WriteStorage<'a, Jobs>,
+  );
+
+  fn run(&mut self, (
+    entities,
+    species,
+    ids,
+    tiles,
+    kinds,
+    elevations,
+    maps,
+    healths,
+    mut packs,
+    mut needs,
+    mut stacks,
+    mut movements,
+    mut jobs
+  ): Self::SystemData) {
+    let topology = (&maps).join().next().map(|tm| tm.topology).unwrap_or_default();
+    let map = build_terrain(&tiles, &kinds, &elevations);
+    let mut wolves: Vec<(Entity, PawnId, Tile)> = (&entities, &species, &ids, &tiles).join()
+      .filter(|(_, spece, _, _)| spece.is_animal())
+      .map(|(e, _, id, tile)| (e, *id, tile.clone()))
+      .collect();
+    wolves.sort_by_key(|(_, id, _)| id.0);
+    let mut humans: Vec<(PawnId, Tile)> = (&species, &ids, &tiles).join()
+      .filter(|(spece, _, _)| !spece.is_animal())
+      .map(|(_, id, tile)| (*id, tile.clone()))
+      .collect();
+    humans.sort_by_key(|(id, _)| id.0);
+    let meat_tiles: Vec<Tile> = (&stacks, &tiles).join()
+      .filter(|(s, _)| s.resource == Resource::PieceOfMeat && s.count > 0)
+      .map(|(_, tile)| tile.clone())
+      .collect();
+
+    // lone wolves join the closest pack around, or start their own
+    for (e, id, tile) in wolves.iter() {
+      if packs.contains(*e) {
+        continue;
+      }
+      let joined = wolves.iter()
+        .filter_map(|(other, _, at)| packs.get(*other).map(|p| (p, at)))
+        .filter(|(_, at)| topology.distance(tile, at) <= JOIN_RANGE)
+        .min_by_key(|(p, at)| (topology.distance(tile, at), p.leader.0))
+        .map(|(p, _)| p.clone());
+      let pack = joined.unwrap_or_else(|| Pack { leader: *id, den: tile.clone() });
+      if let Err(err) = packs.insert(*e, pack) {
+        println!("cannot join pack: {}", err);
+      }
+    }
+    let mut pack_sizes: HashMap<PawnId, usize> = HashMap::new();
+    for (e, _, _) in wolves.iter() {
+      if let Some(p) = packs.get(*e) {
+        *pack_sizes.entry(p.leader).or_insert(0) += 1;
+      }
+    }
+
+    for (e, _, tile) in wolves {
+      let pack = match packs.get(e) {
+        None => continue,
+        Some(p) => p.clone()
+      };
+      let (movement, wolf_jobs) = match (movements.get_mut(e), jobs.get_mut(e)) {
+        (Some(m), Some(j)) => (m, j),
+        _ => continue
+      };
+      let hunger = needs.get(e).map(|n| n.hunger).unwrap_or(1.0);
+      let health = healths.get(e).map(|h| h.0).unwrap_or(1.0);
+
+      // hurt wolves run from people and give up the hunt
+      if health < FLEE_HEALTH {
+        if let Some((_, threat)) = nearest(topology, &tile, &humans, FLEE_RANGE) {
+          wolf_jobs.current = Some(Job::Flee);
+          if movement.is_idle() {
+            if let Some(step) = step_towards(topology, &map, &tile, threat, true) {
+              movement.path.push_back(step);
+            }
+          }
+          continue;
+        }
+      }
+      if !movement.is_idle() {
+        continue;
+      }
+      if let Some(Job::Flee) | Some(Job::Wander) = wolf_jobs.current {
+        wolf_jobs.current = None;
+      }
+
+      // hungry wolves eat meat lying under them, or go for the closest meat in the territory
+      if hunger < HUNT_HUNGER {
+        let meat = (&mut stacks, &tiles).join()
+          .find(|(s, t)| s.resource == Resource::PieceOfMeat && s.count > 0 && t.position == tile.position)
+          .map(|(s, _)| s);
+        if let Some(stack) = meat {
+          stack.count -= 1;
+          if let Some(n) = needs.get_mut(e) {
+            n.hunger = (n.hunger + MEAT_NOURISHMENT).min(1.0);
+          }
+          continue;
+        }
+        let food = meat_tiles.iter()
+          .filter(|at| topology.distance(&pack.den, at) <= TERRITORY_RADIUS)
+          .min_by_key(|at| (topology.distance(&tile, at), at.position.x, at.position.y));
+        if let Some(at) = food {
+          if let Some(step) = step_towards(topology, &map, &tile, at, false) {
+            movement.path.push_back(step);
+            continue;
+          }
+        }
+      }
+
+      // keep after the prey, unless it ran too far from the den
+      if let Some(target) = attack_target(wolf_jobs) {
+        let prey = humans.iter().find(|(id, _)| *id == target);
+        match prey {
+          Some((_, at)) if topology.distance(&pack.den, at) <= CHASE_RANGE => {
+            if topology.distance(&tile, at) > REACH {
+              if let Some(step) = step_towards(topology, &map, &tile, at, false) {
+                movement.path.push_back(step);
+              }
+            }
+            continue;
+          }
+          _ => wolf_jobs.current = None,
+        }
+      }
+
+      let brave = pack_sizes.get(&pack.leader).cloned().unwrap_or(0) >= PACK_COURAGE;
+      if hunger < HUNT_HUNGER || brave {
+        let prey = nearest(topology, &tile, &humans, HUNT_RANGE)
+          .filter(|(_, at)| topology.distance(&pack.den, at) <= TERRITORY_RADIUS);
+        if let Some((id, _)) = prey {
+          wolf_jobs.current = Some(Job::Attack(*id));
+          continue;
+        }
+      }
+
+      if let Some(step) = self.wander(topology, &map, &tile, &pack.den) {
+        movement.path.push_back(step);
+        wolf_jobs.current = Some(Job::Wander);
+      }
+    }
+    // eaten meat disappears, other empty stacks are not the wolves' business
+    for (e, stack) in (&entities, &stacks).join() {
+      if stack.resource == Resource::PieceOfMeat && stack.count == 0 {
+        if let Err(err) = entities.delete(e) {
+          println!("cannot remove eaten meat: {}", err);
+        }
+      }
+    }
+  }
+
+  fn setup(&mut self, res: &mut Resources) {
+    use amethyst::ecs::SystemData;
+    Self::SystemData::setup(res);
+    self.rng = Rng::from_seed(&res.entry::<GameSeed>().or_insert_with(GameSeed::default), 6);
+  }
+}
@@ -0,0 +1,390 @@
+use std::collections::{ HashMap, HashSet };
+use amethyst::{
+  core::nalgebra::{ Vector2, Vector3 },
+  ecs::{ Entity, Join },
+  input::{ is_key_down, is_close_requested },
+  prelude::*,
+  renderer::Camera,
+  winit::{ MouseButton, VirtualKeyCode },
+};
+use super::{ MainMenuState, grab_cursor, free_cursor, delete_all };
+use crate::cursor::tile_picking_system::PickedTile;
+use crate::game_state::{ TileMapTilesKind, initialize_camera };
+use crate::input::InputState;
+use crate::random::GameSeed;
+use crate::rendering::tile_map::{ Tile, TileMap, TileSprite, Elevation, GridTopology, create_tile, tile_sprite };
+use crate::simulation::{ Clock, Visibility, WeatherState };
+use crate::scenario::{ Scenario, SelectedScenario, MapFile, MapTile, MAP_DIR, build_map };
+use crate::ui::text_panel::{ Anchor, TextPanel, create_text_panel };
+
+const MAX_BRUSH_RADIUS: i32 = 8;
+const KINDS: [TileMapTilesKind; 3] = [TileMapTilesKind::Grass, TileMapTilesKind::Dirt, TileMapTilesKind::Water];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Brush {
+  Kind,
+  Elevation,
+  AddTiles,
+  RemoveTiles,
+  Fill,
+}
+
+impl Brush {
+  fn next(self) -> Self {
+    match self {
+      Brush::Kind => Brush::Elevation,
+      Brush::Elevation => Brush::AddTiles,
+      Brush::AddTiles => Brush::RemoveTiles,
+      Brush::RemoveTiles => Brush::Fill,
+      Brush::Fill => Brush::Kind,
+    }
+  }
+}
+
+// what a tile looked like, None when there was no tile at all
+type TileState = Option<(TileMapTilesKind, Elevation)>;
+
+struct TileEdit {
+  position: Vector3<i32>,
+  before: TileState,
+  after: TileState,
+}
+
+// all tiles touched by one press of the mouse button, undone at once
+#[derive(Default)]
+struct Stroke {
+  edits: Vec<TileEdit>,
+}
+
+impl Stroke {
+  fn record(&mut self, position: Vector3<i32>, before: TileState, after: TileState) {
+    match self.edits.iter_mut().find(|e| e.position == position) {
+      Some(edit) => edit.after = after,
+      None => self.edits.push(TileEdit { position, before, after }),
+    }
+  }
+}
+
+// editor settings, other game tools stay idle while it is active
+pub struct MapEditor {
+  pub active: bool,
+  pub brush: Brush,
+  pub radius: i32,
+  pub kind: usize,
+  pub elevation: i32,
+}
+
+impl Default for MapEditor {
+  fn default() -> Self {
+    MapEditor {
+      active: false,
+      brush: Brush::Kind,
+      radius: 0,
+      kind: 0,
+      elevation: 0,
+    }
+  }
+}
+
+impl MapEditor {
+  pub fn kind(&self) -> TileMapTilesKind {
+    KINDS[self.kind]
+  }
+
+  fn describe(&self) -> String {
+    let brush = match self.brush {
+      Brush::Kind => format!("paint {:?}", self.kind()),
+      Brush::Elevation => format!("elevation {}", self.elevation),
+      Brush::AddTiles => format!("add {:?} tiles", self.kind()),
+      Brush::RemoveTiles => "remove tiles".to_string(),
+      Brush::Fill => format!("fill with {:?}", self.kind()),
+    };
+    format!(
+      "map editor: {}, radius {}\n\
+       B brush, K kind, [ ] radius, PgUp PgDn elevation\n\
+       Ctrl+Z undo, Ctrl+Y redo, Ctrl+S save, Esc menu",
+      brush, self.radius
+    )
+  }
+}
+
+fn tile_state(world: &World, position: &Vector3<i32>) -> (Option<Entity>, TileState) {
+  let entities = world.entities();
+  let tiles = world.read_storage::<Tile>();
+  let kinds = world.read_storage::<TileMapTilesKind>();
+  let elevations = world.read_storage::<Elevation>();
+  let found = (&entities, &tiles, &kinds).join()
+    .find(|(_, t, _)| t.position == *position)
+    .map(|(e, _, kind)| (Some(e), Some((*kind, elevations.get(e).cloned().unwrap_or_default()))))
+    .unwrap_or((None, None));
+  found
+}
+
+fn set_tile(world: &mut World, position: Vector3<i32>, state: TileState) {
+  let (entity, _) = tile_state(world, &position);
+  match (entity, state) {
+    (None, None) => {}
+    (Some(e), None) => {
+      if let Err(err) = world.delete_entity(e) {
+        println!("cannot remove tile: {}", err);
+      }
+    }
+    (None, Some((kind, elevation))) => {
+      create_tile(world, position, kind, elevation);
+    }
+    (Some(e), Some((kind, elevation))) => {
+      let results = [
+        world.write_storage::<TileMapTilesKind>().insert(e, kind).map(|_| ()),
+        world.write_storage::<TileSprite>().insert(e, tile_sprite(kind)).map(|_| ()),
+        world.write_storage::<Elevation>().insert(e, elevation).map(|_| ()),
+      ];
+      for r in results.iter() {
+        if let Err(err) = r {
+          println!("cannot change tile: {}", err);
+        }
+      }
+    }
+  }
+}
+
+fn map_topology(world: &World) -> GridTopology {
+  let maps = world.read_storage::<TileMap>();
+  let topology = (&maps).join().next().map(|tm| tm.topology).unwrap_or_default();
+  topology
+}
+
+// connected tiles of the same kind, starting from the given one
+fn flood_region(world: &World, topology: GridTopology, start: &Tile) -> Vec<Vector3<i32>> {
+  let tiles = world.read_storage::<Tile>();
+  let kinds = world.read_storage::<TileMapTilesKind>();
+  let map: HashMap<Vector3<i32>, TileMapTilesKind> = (&tiles, &kinds).join()
+    .map(|(t, k)| (t.position, *k))
+    .collect();
+  let kind_at = |p: &Vector3<i32>| map.get(p).cloned();
+  let target = match kind_at(&start.position) {
+    None => return Vec::new(),
+    Some(k) => k
+  };
+  let mut visited = HashSet::<Vector3<i32>>::new();
+  let mut front = vec![start.clone()];
+  visited.insert(start.position);
+  while let Some(tile) = front.pop() {
+    for n in topology.neighbours(&tile) {
+      if !visited.contains(&n.position) && kind_at(&n.position) == Some(target) {
+        visited.insert(n.position);
+        front.push(n);
+      }
+    }
+  }
+  visited.into_iter().collect()
+}
+
+pub struct EditorState {
+  window_resolution: Vector2<u16>,
+  undo: Vec<Stroke>,
+  redo: Vec<Stroke>,
+  stroke: Option<Stroke>,
+  status: Option<Entity>,
+}
+
+impl EditorState {
+  pub fn new(width: u16, height: u16) -> Self {
+    EditorState {
+      window_resolution: Vector2::<u16>::new(width, height),
+      undo: Vec::new(),
+      redo: Vec::new(),
+      stroke: None,
+      status: None,
+    }
+  }
+
+  fn paint(&mut self, world: &mut World, tile: &Tile) {
+    let (brush, radius, kind, elevation) = {
+      let editor = world.read_resource::<MapEditor>();
+      (editor.brush, editor.radius, editor.kind(), Elevation(editor.elevation))
+    };
+    let topology = map_topology(world);
+    let positions = match brush {
+      Brush::Fill => flood_region(world, topology, tile),
+      _ => topology.area(tile, radius).into_iter().map(|t| t.position).collect(),
+    };
+    let stroke = self.stroke.get_or_insert_with(Stroke::default);
+    for position in positions {
+      let (_, before) = tile_state(world, &position);
+      let after = match (brush, before) {
+        (Brush::Kind, Some((_, e))) | (Brush::Fill, Some((_, e))) => Some((kind, e)),
+        (Brush::Elevation, Some((k, _))) => Some((k, elevation)),
+        (Brush::AddTiles, None) => Some((kind, elevation)),
+        (Brush::RemoveTiles, Some(_)) => None,
+        _ => continue,
+      };
+      if after == before {
+        continue;
+      }
+      set_tile(world, position, after);
+      stroke.record(position, before, after);
+    }
+  }
+
+  fn finish_stroke(&mut self) {
+    if let Some(stroke) = self.stroke.take() {
+      if !stroke.edits.is_empty() {
+        self.undo.push(stroke);
+        self.redo.clear();
+      }
+    }
+  }
+
+  fn undo(&mut self, world: &mut World) {
+    if let Some(stroke) = self.undo.pop() {
+      for edit in stroke.edits.iter().rev() {
+        set_tile(world, edit.position, edit.before);
+      }
+      self.redo.push(stroke);
+    }
+  }
+
+  fn redo(&mut self, world: &mut World) {
+    if let Some(stroke) = self.redo.pop() {
+      for edit in stroke.edits.iter() {
+        set_tile(world, edit.position, edit.after);
+      }
+      self.undo.push(stroke);
+    }
+  }
+
+  fn save(&self, world: &World) {
+    let name = Scenario::from_selection(&world.read_resource::<SelectedScenario>()).name;
+    let path = format!("{}/{}.ron", MAP_DIR, name);
+    let tiles = world.read_storage::<Tile>();
+    let kinds = world.read_storage::<TileMapTilesKind>();
+    let elevations = world.read_storage::<Elevation>();
+    let mut map = MapFile {
+      topology: map_topology(world),
+      tiles: (&tiles, &kinds, (&elevations).maybe()).join()
+        .map(|(t, kind, elevation)| MapTile {
+          at: (t.position.x, t.position.y),
+          kind: *kind,
+          elevation: elevation.map(|e| e.0).unwrap_or(0),
+        })
+        .collect()
+    };
+    map.tiles.sort_by_key(|t| t.at);
+    match map.save(&path) {
+      Ok(()) => println!("map saved to {}, set map.file in a scenario to use it", path),
+      Err(e) => println!("{}", e),
+    }
+  }
+
+  fn handle_keys(&mut self, world: &mut World) {
+    let (ctrl, keys) = {
+      let input = world.read_resource::<InputState>();
+      let ctrl = input.is_key_pressed(VirtualKeyCode::LControl) || input.is_key_pressed(VirtualKeyCode::RControl);
+      (ctrl, input.keys_down.clone())
+    };
+    if ctrl {
+      if keys.contains(&VirtualKeyCode::Z) {
+        self.undo(world);
+      }
+      if keys.contains(&VirtualKeyCode::Y) {
+        self.redo(world);
+      }
+      if keys.contains(&VirtualKeyCode::S) {
+        self.save(world);
+      }
+      return;
+    }
+    let mut editor = world.write_resource::<MapEditor>();
+    for key in keys {
+      match key {
+        VirtualKeyCode::B => editor.brush = editor.brush.next(),
+        VirtualKeyCode::K => editor.kind = (editor.kind + 1) % KINDS.len(),
+        VirtualKeyCode::LBracket => editor.radius = (editor.radius - 1).max(0),
+        VirtualKeyCode::RBracket => editor.radius = (editor.radius + 1).min(MAX_BRUSH_RADIUS),
+        VirtualKeyCode::PageUp => editor.elevation += 1,
+        VirtualKeyCode::PageDown => editor.elevation -= 1,
+        _ => {}
+      }
+    }
+  }
+}
+
+// map of the selected scenario without pawns and resources, loading state waits for its texture
+pub fn load_editor_world(world: &mut World) {
+  let selected = world.res.entry::<SelectedScenario>().or_insert_with(SelectedScenario::default).clone();
+  let scenario = Scenario::from_selection(&selected);
+  let seed = *world.res.entry::<GameSeed>().or_insert_with(GameSeed::default);
+  build_map(world, &scenario.map, &seed, true);
+}
+
+impl SimpleState for EditorState {
+  fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+    let world = data.world;
+    world.res.entry::<MapEditor>().or_insert_with(MapEditor::default).active = true;
+    // fog of war, night and rain left from a previous game, editor sees the whole map in daylight
+    world.add_resource(Visibility::default());
+    world.add_resource(Clock::default());
+    world.add_resource(WeatherState::default());
+    initialize_camera(world, &self.window_resolution);
+    self.status = Some(create_text_panel(world, Anchor::TopLeft, (310.0, -50.0), (600.0, 80.0)));
+    grab_cursor(world);
+  }
+
+  fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+    let world = data.world;
+    world.write_resource::<MapEditor>().active = false;
+    // editor leaves nothing behind, the game builds its own map and camera
+    let mut doomed: Vec<Entity> = {
+      let entities = world.entities();
+      let tiles = world.read_storage::<Tile>();
+      let maps = world.read_storage::<TileMap>();
+      let cameras = world.read_storage::<Camera>();
+      let mut found: Vec<Entity> = (&entities, &tiles).join().map(|(e, _)| e).collect();
+      found.extend((&entities, &maps).join().map(|(e, _)| e));
+      found.extend((&entities, &cameras).join().map(|(e, _)| e));
+      found
+    };
+    doomed.extend(self.status.take());
+    delete_all(world, &mut doomed);
+    free_cursor(world);
+  }
+
+  fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+    let world = &mut *data.world;
+    self.handle_keys(world);
+    let (pressed, picked) = {
+      let input = world.read_resource::<InputState>();
+      (input.is_button_pressed(MouseButton::Left), world.read_resource::<PickedTile>().tile.clone())
+    };
+    match (pressed, picked) {
+      (true, Some(tile)) => self.paint(world, &tile),
+      (false, _) => self.finish_stroke(),
+      _ => {}
+    }
+    let description = world.read_resource::<MapEditor>().describe();
+    if let Some(status) = self.status {
+      let mut panels = world.write_storage::<TextPanel>();
+      if let Some(panel) = panels.get_mut(status) {
+        if panel.text != description {
+          panel.text = description;
+        }
+      }
+    }
+    Trans::None
+  }
+
+  fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+    match &event {
+      StateEvent::Window(event) => {
+        if is_close_requested(&event) {
+          free_cursor(data.world);
+          Trans::Quit
+        } else if is_key_down(&event, VirtualKeyCode::Escape) {
+          let (w, h) = (self.window_resolution.x, self.window_resolution.y);
+          Trans::Switch(Box::new(MainMenuState::new(w, h)))
+        } else { Trans::None }
+      }
+      _ => Trans::None
+    }
+  }
+}
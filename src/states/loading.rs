@@ -0,0 +1,74 @@
+use amethyst::{
+  core::nalgebra::{ Vector2 },
+  input::{ is_close_requested },
+  prelude::*,
+};
+use super::{ EditorState, free_cursor };
+use super::editor::load_editor_world;
+use crate::asset_loader::AssetRegistry;
+use crate::cursor::create_cursor;
+use crate::game_state::{ self, State };
+
+pub struct LoadingState {
+  window_resolution: Vector2<u16>,
+  // map editor is started instead of the game
+  editor: bool,
+}
+
+impl LoadingState {
+  pub fn new(width: u16, height: u16) -> Self {
+    LoadingState {
+      window_resolution: Vector2::<u16>::new(width, height),
+      editor: false,
+    }
+  }
+
+  pub fn editor(width: u16, height: u16) -> Self {
+    LoadingState {
+      window_resolution: Vector2::<u16>::new(width, height),
+      editor: true,
+    }
+  }
+}
+
+impl SimpleState for LoadingState {
+  fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+    let world = data.world;
+    free_cursor(world);
+    world.res.entry::<AssetRegistry>().or_insert_with(AssetRegistry::default);
+    create_cursor(world);
+    if self.editor {
+      load_editor_world(world);
+    } else {
+      game_state::load_world(world);
+    }
+  }
+
+  fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+    let registry = data.world.read_resource::<AssetRegistry>();
+    if !registry.is_complete() {
+      return Trans::None;
+    }
+    let (_, total) = registry.progress();
+    let failures = registry.failures();
+    if !failures.is_empty() {
+      println!("{} of {} assets failed to load:", failures.len(), total);
+      for path in failures {
+        println!("  {}", path);
+      }
+    }
+    let (w, h) = (self.window_resolution.x, self.window_resolution.y);
+    if self.editor {
+      Trans::Switch(Box::new(EditorState::new(w, h)))
+    } else {
+      Trans::Switch(Box::new(State::new(w, h)))
+    }
+  }
+
+  fn handle_event(&mut self, _data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+    match &event {
+      StateEvent::Window(event) if is_close_requested(&event) => Trans::Quit,
+      _ => Trans::None
+    }
+  }
+}
@@ -0,0 +1,186 @@
+use std::path::Path;
+use amethyst::{
+  core::nalgebra::{ Vector2 },
+  ecs::Entity,
+  input::{ is_key_down, is_close_requested },
+  prelude::*,
+  winit::VirtualKeyCode,
+};
+use super::{ LoadingState, LAST_SESSION, grab_cursor, free_cursor, delete_all };
+use crate::cursor::create_cursor;
+use crate::random::GameSeed;
+use crate::replay::Replay;
+use crate::scenario::{ Scenario, SelectedScenario, available_scenarios };
+use crate::ui::button::{ create_button, clicked_button, screen_center };
+use crate::ui::text_panel::{ Anchor, TextPanel, create_text_panel };
+
+const NEW_GAME: u32 = 0;
+const LOAD: u32 = 1;
+const QUIT: u32 = 2;
+const SCENARIO: u32 = 3;
+const EDITOR: u32 = 4;
+const MENU_LAYER: u8 = 10;
+
+pub struct MainMenuState {
+  window_resolution: Vector2<u16>,
+  buttons: Vec<Entity>,
+  // shows which scenario a new game starts with
+  scenario_status: Option<Entity>,
+}
+
+impl MainMenuState {
+  pub fn new(width: u16, height: u16) -> Self {
+    MainMenuState {
+      window_resolution: Vector2::<u16>::new(width, height),
+      buttons: Vec::new(),
+      scenario_status: None,
+    }
+  }
+
+  fn new_game(&self, world: &mut World) -> SimpleTrans {
+    let seed = world.read_resource::<GameSeed>().0;
+    let scenario = world.read_resource::<SelectedScenario>().0.clone();
+    let recording = !world.read_resource::<Replay>().is_idle();
+    if !recording {
+      world.add_resource(Replay::record(LAST_SESSION.to_string(), seed, scenario));
+    }
+    self.start()
+  }
+
+  // Loading replays last session from scratch up to the tick it was saved at,
+  // so world ends up in the same state. Recording goes on into the same file.
+  fn load(&self, world: &mut World) -> SimpleTrans {
+    if !Path::new(LAST_SESSION).exists() {
+      println!("there is no saved session at {}", LAST_SESSION);
+      return Trans::None;
+    }
+    match Replay::resume(LAST_SESSION) {
+      Ok((replay, seed, scenario)) => {
+        world.add_resource(replay);
+        world.add_resource(GameSeed(seed));
+        world.add_resource(SelectedScenario(scenario));
+        self.start()
+      }
+      Err(e) => {
+        println!("{}", e);
+        Trans::None
+      }
+    }
+  }
+
+  // cycles through scenario files, built-in debug scenario goes first
+  fn next_scenario(&self, world: &mut World) {
+    let scenarios = available_scenarios();
+    {
+      let mut selected = world.write_resource::<SelectedScenario>();
+      let position = selected.0.as_ref().and_then(|s| scenarios.iter().position(|p| p == s));
+      selected.0 = match position {
+        None => scenarios.first().cloned(),
+        Some(i) => scenarios.get(i + 1).cloned()
+      };
+    }
+    self.show_scenario(world);
+  }
+
+  // selected file is parsed right away, so a broken one is reported before the game starts
+  fn show_scenario(&self, world: &World) {
+    let description = match &world.read_resource::<SelectedScenario>().0 {
+      None => "Scenario: debug".to_string(),
+      Some(path) => match Scenario::load(path) {
+        Ok(scenario) => format!("Scenario: {}\n{}", scenario.name, path),
+        Err(e) => format!("{}, debug scenario is used instead", e)
+      }
+    };
+    let mut panels = world.write_storage::<TextPanel>();
+    if let Some(panel) = self.scenario_status.and_then(|e| panels.get_mut(e)) {
+      panel.text = description;
+    }
+  }
+
+  fn editor(&self) -> SimpleTrans {
+    let (w, h) = (self.window_resolution.x, self.window_resolution.y);
+    Trans::Switch(Box::new(LoadingState::editor(w, h)))
+  }
+
+  fn start(&self) -> SimpleTrans {
+    let (w, h) = (self.window_resolution.x, self.window_resolution.y);
+    Trans::Switch(Box::new(LoadingState::new(w, h)))
+  }
+}
+
+impl SimpleState for MainMenuState {
+  fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+    let world = data.world;
+    create_cursor(world);
+    // replay given from command line is reproduced right away, without going through the menu
+    if world.read_resource::<Replay>().is_replaying() {
+      return;
+    }
+    let center = screen_center(world);
+    let size = Vector2::<f32>::new(256.0, 64.0);
+    let step = Vector2::<f32>::new(0.0, size.y * 1.5);
+    let items = [
+      (NEW_GAME, "./resources/sprites/ui/menu_new_game.png"),
+      (LOAD, "./resources/sprites/ui/menu_load.png"),
+      (SCENARIO, "./resources/sprites/ui/menu_scenario.png"),
+      (EDITOR, "./resources/sprites/ui/menu_editor.png"),
+      (QUIT, "./resources/sprites/ui/menu_quit.png"),
+    ];
+    for (i, (id, texture)) in items.iter().enumerate() {
+      let at = center + step * 1.5 - step * i as f32;
+      self.buttons.push(create_button(world, texture, at, size, MENU_LAYER, *id));
+    }
+    // next to the scenario button
+    let status = create_text_panel(world, Anchor::Middle, (size.x / 2.0 + 176.0, step.y * -0.5), (320.0, 48.0));
+    self.buttons.push(status);
+    self.scenario_status = Some(status);
+    self.show_scenario(world);
+    grab_cursor(world);
+  }
+
+  fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+    delete_all(data.world, &mut self.buttons);
+    self.scenario_status = None;
+  }
+
+  fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+    if data.world.read_resource::<Replay>().is_replaying() {
+      return self.start();
+    }
+    match clicked_button(data.world) {
+      Some(NEW_GAME) => self.new_game(data.world),
+      Some(LOAD) => self.load(data.world),
+      Some(SCENARIO) => {
+        self.next_scenario(data.world);
+        Trans::None
+      }
+      Some(EDITOR) => self.editor(),
+      Some(QUIT) => {
+        free_cursor(data.world);
+        Trans::Quit
+      }
+      _ => Trans::None
+    }
+  }
+
+  fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+    match &event {
+      StateEvent::Window(event) => {
+        if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
+          free_cursor(data.world);
+          Trans::Quit
+        } else if is_key_down(&event, VirtualKeyCode::Return) || is_key_down(&event, VirtualKeyCode::N) {
+          self.new_game(data.world)
+        } else if is_key_down(&event, VirtualKeyCode::L) {
+          self.load(data.world)
+        } else if is_key_down(&event, VirtualKeyCode::E) {
+          self.editor()
+        } else if is_key_down(&event, VirtualKeyCode::S) {
+          self.next_scenario(data.world);
+          Trans::None
+        } else { Trans::None }
+      }
+      _ => Trans::None
+    }
+  }
+}
@@ -0,0 +1,35 @@
+pub mod main_menu;
+pub mod loading;
+pub mod pause;
+pub mod editor;
+pub use self::main_menu::MainMenuState;
+pub use self::loading::LoadingState;
+pub use self::pause::{ PauseState, PauseMenu };
+pub use self::editor::EditorState;
+
+use amethyst::{
+  ecs::{ Entity, World },
+  renderer::WindowMessages,
+};
+use crate::input::{ InputConfig, capture_cursor, release_cursor };
+
+pub const LAST_SESSION: &str = "./saves/last_session.ron";
+
+pub fn grab_cursor(world: &World) {
+  let mode = world.read_resource::<InputConfig>().cursor_mode;
+  let mut msg = world.write_resource::<WindowMessages>();
+  capture_cursor(&mut msg, mode);
+}
+
+pub fn free_cursor(world: &World) {
+  let mut msg = world.write_resource::<WindowMessages>();
+  release_cursor(&mut msg);
+}
+
+pub fn delete_all(world: &mut World, entities: &mut Vec<Entity>) {
+  for e in entities.drain(..) {
+    if let Err(e) = world.delete_entity(e) {
+      println!("cannot delete entity: {}", e);
+    }
+  }
+}
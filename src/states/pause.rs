@@ -0,0 +1,78 @@
+use amethyst::{
+  core::nalgebra::{ Vector2 },
+  ecs::Entity,
+  input::{ is_key_down, is_close_requested },
+  prelude::*,
+  winit::VirtualKeyCode,
+};
+use super::{ grab_cursor, free_cursor, delete_all };
+use crate::ui::button::{ create_button, clicked_button, screen_center };
+
+const RESUME: u32 = 0;
+const QUIT: u32 = 1;
+const PAUSE_LAYER: u8 = 20;
+
+// Set while the pause menu is shown. Global systems keep running under it,
+// so the ones reacting to game input check it and stay idle.
+#[derive(Default)]
+pub struct PauseMenu {
+  pub open: bool,
+}
+
+// overlay pushed on top of the game, the game underneath does not update while it is shown
+pub struct PauseState {
+  entities: Vec<Entity>,
+}
+
+impl PauseState {
+  pub fn new() -> Self {
+    PauseState {
+      entities: Vec::new(),
+    }
+  }
+}
+
+impl SimpleState for PauseState {
+  fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+    let world = data.world;
+    world.add_resource(PauseMenu { open: true });
+    let center = screen_center(world);
+    let shade = create_button(world, "./resources/sprites/ui/pause_shade.png", center, center * 2.0, PAUSE_LAYER, u32::max_value());
+    self.entities.push(shade);
+    let size = Vector2::<f32>::new(256.0, 64.0);
+    let step = Vector2::<f32>::new(0.0, size.y * 0.75);
+    self.entities.push(create_button(world, "./resources/sprites/ui/menu_resume.png", center + step, size, PAUSE_LAYER + 1, RESUME));
+    self.entities.push(create_button(world, "./resources/sprites/ui/menu_quit.png", center - step, size, PAUSE_LAYER + 1, QUIT));
+    grab_cursor(world);
+  }
+
+  fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+    data.world.add_resource(PauseMenu { open: false });
+    delete_all(data.world, &mut self.entities);
+  }
+
+  fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+    match clicked_button(data.world) {
+      Some(RESUME) => Trans::Pop,
+      Some(QUIT) => {
+        free_cursor(data.world);
+        Trans::Quit
+      }
+      _ => Trans::None
+    }
+  }
+
+  fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+    match &event {
+      StateEvent::Window(event) => {
+        if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Q) {
+          free_cursor(data.world);
+          Trans::Quit
+        } else if is_key_down(&event, VirtualKeyCode::Escape) {
+          Trans::Pop
+        } else { Trans::None }
+      }
+      _ => Trans::None
+    }
+  }
+}
@@ -0,0 +1,186 @@
+use amethyst::ecs::{ Entity, Join, World };
+use crate::game_state::TileMapTilesKind;
+use crate::pawn::{ Spece, Sex, Race, Complex, PawnId };
+use crate::rendering::tile_map::{ Tile, TileSprite, tile_sprite };
+use crate::resource::{ Resource, ResourceStack };
+use crate::simulation::{ Fire, Plant };
+
+pub mod tool_system;
+pub use self::tool_system::ToolSystem;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+  PlacePawn,
+  PlaceResource,
+  PaintTerrain,
+  Erase,
+  Measure,
+  PlaceFire,
+}
+
+pub const TOOLS: [Tool; 6] = [
+  Tool::PlacePawn,
+  Tool::PlaceResource,
+  Tool::PaintTerrain,
+  Tool::PlaceFire,
+  Tool::Erase,
+  Tool::Measure,
+];
+
+const RESOURCES: [Resource; 2] = [Resource::Apple, Resource::PieceOfMeat];
+const TERRAINS: [TileMapTilesKind; 3] = [TileMapTilesKind::Grass, TileMapTilesKind::Dirt, TileMapTilesKind::Water];
+const FIRES: [Fire; 2] = [Fire::Campfire, Fire::Torch];
+const RESOURCE_COUNT: u32 = 5;
+
+pub fn all_speces() -> Vec<Spece> {
+  let sexes = [Sex::Male, Sex::Female];
+  let races = [Race::Euro, Race::Afro, Race::Asian, Race::Indean];
+  let complexes = [Complex::Athletic, Complex::Skinny, Complex::Obese];
+  let mut speces = Vec::new();
+  for sex in sexes.iter() {
+    for race in races.iter() {
+      for complex in complexes.iter() {
+        speces.push(Spece::Human(*sex, *race, *complex));
+      }
+    }
+  }
+  for sex in sexes.iter() {
+    speces.push(Spece::Wolf(*sex));
+  }
+  speces
+}
+
+// Current tool with its options. Choosing the active tool again cycles its option.
+pub struct ToolPalette {
+  pub tool: Tool,
+  pub speces: Vec<Spece>,
+  pub spece: usize,
+  pub resource: usize,
+  pub resource_count: u32,
+  pub terrain: usize,
+  pub fire: usize,
+  pub measure_from: Option<Tile>,
+  pub last_distance: Option<i32>,
+}
+
+impl Default for ToolPalette {
+  fn default() -> Self {
+    ToolPalette {
+      tool: Tool::PlacePawn,
+      speces: all_speces(),
+      spece: 0,
+      resource: 0,
+      resource_count: RESOURCE_COUNT,
+      terrain: 0,
+      fire: 0,
+      measure_from: None,
+      last_distance: None,
+    }
+  }
+}
+
+impl ToolPalette {
+  pub fn select(&mut self, tool: Tool) {
+    if tool != self.tool {
+      self.tool = tool;
+      self.measure_from = None;
+      self.last_distance = None;
+      return;
+    }
+    match tool {
+      Tool::PlacePawn => self.spece = (self.spece + 1) % self.speces.len(),
+      Tool::PlaceResource => self.resource = (self.resource + 1) % RESOURCES.len(),
+      Tool::PaintTerrain => self.terrain = (self.terrain + 1) % TERRAINS.len(),
+      Tool::PlaceFire => self.fire = (self.fire + 1) % FIRES.len(),
+      Tool::Erase => {}
+      Tool::Measure => {
+        self.measure_from = None;
+        self.last_distance = None;
+      }
+    }
+  }
+
+  pub fn spece(&self) -> Spece {
+    self.speces[self.spece]
+  }
+
+  pub fn resource(&self) -> Resource {
+    RESOURCES[self.resource]
+  }
+
+  pub fn terrain(&self) -> TileMapTilesKind {
+    TERRAINS[self.terrain]
+  }
+
+  pub fn fire(&self) -> Fire {
+    FIRES[self.fire]
+  }
+
+  pub fn describe(&self) -> String {
+    match self.tool {
+      Tool::PlacePawn => format!("place pawn: {:?}", self.spece()),
+      Tool::PlaceResource => format!("place resource: {} x {:?}", self.resource_count, self.resource()),
+      Tool::PaintTerrain => format!("paint terrain: {:?}", self.terrain()),
+      Tool::PlaceFire => format!("place fire: {:?}", self.fire()),
+      Tool::Erase => "erase pawn, or resources, fire and plants".to_string(),
+      Tool::Measure => match (&self.measure_from, self.last_distance) {
+        (_, Some(d)) => format!("measure: {} tiles", d),
+        (Some(from), None) => format!("measure from {}, {}, {}", from.position.x, from.position.y, from.position.z),
+        (None, None) => "measure: pick first tile".to_string(),
+      }
+    }
+  }
+}
+
+pub fn paint_terrain(world: &mut World, tile: &Tile, kind: TileMapTilesKind) {
+  let entity = {
+    let entities = world.entities();
+    let tiles = world.read_storage::<Tile>();
+    let kinds = world.read_storage::<TileMapTilesKind>();
+    let found = (&entities, &tiles, &kinds).join()
+      .find(|(_, t, _)| t.position == tile.position)
+      .map(|(e, _, _)| e);
+    found
+  };
+  if let Some(e) = entity {
+    if let Err(err) = world.write_storage::<TileMapTilesKind>().insert(e, kind) {
+      println!("cannot paint tile: {}", err);
+    }
+    if let Err(err) = world.write_storage::<TileSprite>().insert(e, tile_sprite(kind)) {
+      println!("cannot paint tile: {}", err);
+    }
+  }
+}
+
+// removes the pawn with the lowest id on the tile, or everything else on it if there is no pawn
+pub fn erase_at(world: &mut World, tile: &Tile) {
+  world.register::<PawnId>();
+  world.register::<ResourceStack>();
+  world.register::<Fire>();
+  world.register::<Plant>();
+  let doomed: Vec<Entity> = {
+    let entities = world.entities();
+    let tiles = world.read_storage::<Tile>();
+    let ids = world.read_storage::<PawnId>();
+    let stacks = world.read_storage::<ResourceStack>();
+    let fires = world.read_storage::<Fire>();
+    let plants = world.read_storage::<Plant>();
+    let pawn = (&entities, &ids, &tiles).join()
+      .filter(|(_, _, t)| t.position == tile.position)
+      .min_by_key(|(_, id, _)| id.0)
+      .map(|(e, _, _)| e);
+    match pawn {
+      Some(p) => vec![p],
+      None => (&entities, &stacks, &tiles).join()
+        .map(|(e, _, t)| (e, t))
+        .chain((&entities, &fires, &tiles).join().map(|(e, _, t)| (e, t)))
+        .chain((&entities, &plants, &tiles).join().map(|(e, _, t)| (e, t)))
+        .filter(|(_, t)| t.position == tile.position)
+        .map(|(e, _)| e)
+        .collect()
+    }
+  };
+  if let Err(e) = world.delete_entities(&doomed) {
+    println!("cannot erase: {}", e);
+  }
+}
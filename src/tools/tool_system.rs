@@ -0,0 +1,81 @@
+use amethyst::{
+  core::{
+    specs::{
+      prelude::{
+        Join, Read, ReadStorage, Write, System,
+      }
+    },
+  },
+};
+use winit::{ MouseButton };
+
+use crate::cursor::tile_picking_system::PickedTile;
+use crate::input::InputState;
+use crate::states::PauseMenu;
+use crate::rendering::tile_map::TileMap;
+use crate::ui::button::HoveredButton;
+use crate::states::editor::MapEditor;
+use crate::game_messages::{ GameMessageResource, GameMessage };
+use super::{ Tool, ToolPalette };
+
+// dispatches clicks on the map to the active tool
+pub struct ToolSystem;
+
+impl<'a> System<'a> for ToolSystem {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PauseMenu>,
+    Read<'a, PickedTile>,
+    Read<'a, HoveredButton>,
+    Read<'a, MapEditor>,
+    Write<'a, ToolPalette>,
+    Write<'a, GameMessageResource>,
+    ReadStorage<'a, TileMap>,
+  );
+
+  fn run(&mut self, (input, pause_menu, picked, hovered, editor, mut palette, mut game_messages, maps): Self::SystemData) {
+    if pause_menu.open || !input.is_button_down(MouseButton::Left) || hovered.id.is_some() || editor.active {
+      return;
+    }
+    let tile = match &picked.tile {
+      None => return,
+      Some(t) => t.clone()
+    };
+    // only measuring makes sense outside of the map
+    if picked.entity.is_none() && palette.tool != Tool::Measure {
+      return;
+    }
+    match palette.tool {
+      Tool::PlacePawn => {
+        if let Some(pos) = picked.tile_center {
+          game_messages.messages.push(GameMessage::PlacePawn(palette.spece(), tile, pos));
+        }
+      }
+      Tool::PlaceResource => {
+        game_messages.messages.push(GameMessage::PlaceResource(palette.resource(), palette.resource_count, tile));
+      }
+      Tool::PaintTerrain => {
+        game_messages.messages.push(GameMessage::PaintTerrain(tile, palette.terrain()));
+      }
+      Tool::PlaceFire => {
+        game_messages.messages.push(GameMessage::PlaceFire(palette.fire(), tile));
+      }
+      Tool::Erase => {
+        game_messages.messages.push(GameMessage::Erase(tile));
+      }
+      Tool::Measure => {
+        match palette.measure_from.take() {
+          Some(from) if palette.last_distance.is_none() => {
+            let topology = (&maps).join().next().map(|tm| tm.topology).unwrap_or_default();
+            palette.last_distance = Some(topology.distance(&from, &tile));
+            palette.measure_from = Some(from);
+          }
+          _ => {
+            palette.last_distance = None;
+            palette.measure_from = Some(tile);
+          }
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,114 @@
+use amethyst::{
+  core::{ Hidden, nalgebra::{ Vector2 } },
+  ecs::{ Component, DenseVecStorage, Entity, Join, Read, ReadStorage, System, Write },
+  prelude::{ Builder, World },
+  renderer::{ ScreenDimensions },
+};
+use nalgebra_glm::{ translation2d, vec3 };
+use winit::MouseButton;
+use crate::asset_loader::load_png_texture;
+use crate::input::InputState;
+use crate::rendering::screen_space::screen_rect::{ ScreenRect, Transform2D };
+
+pub struct Button {
+  pub id: u32,
+}
+
+impl Component for Button {
+  type Storage = DenseVecStorage<Self>;
+}
+
+pub fn screen_center(world: &World) -> Vector2<f32> {
+  match world.res.try_fetch::<ScreenDimensions>() {
+    None => Vector2::<f32>::new(0.0, 0.0),
+    Some(sd) => Vector2::<f32>::new(sd.width(), sd.height()) / 2.0
+  }
+}
+
+// Button is centered at `at`, which is given in screen pixels
+pub fn create_button(world: &mut World, texture: &str, at: Vector2<f32>, size: Vector2<f32>, layer: u8, id: u32) -> Entity {
+  world.register::<ScreenRect>();
+  world.register::<Transform2D>();
+  world.register::<Button>();
+  let handle = load_png_texture(world, texture.to_string());
+  let mut rect: ScreenRect = Default::default();
+  rect.size = size;
+  rect.position = -size / 2.0;
+  rect.layer = layer;
+  world.create_entity()
+    .with(rect)
+    .with(handle)
+    .with(Transform2D {
+      model: translation2d(&at)
+    })
+    .with(Button { id })
+    .build()
+}
+
+fn find_button(
+  rects: &ReadStorage<ScreenRect>,
+  transforms: &ReadStorage<Transform2D>,
+  buttons: &ReadStorage<Button>,
+  hidden: &ReadStorage<Hidden>,
+  cursor: &Vector2<f32>
+) -> Option<u32> {
+  (rects, transforms, buttons, !hidden).join()
+    .filter(|(rect, transform, _, _)| {
+      let corner = (transform.model * vec3(rect.position.x, rect.position.y, 1.0)).xy();
+      let local = cursor - corner;
+      local.x >= 0.0 && local.y >= 0.0 && local.x <= rect.size.x && local.y <= rect.size.y
+    })
+    .max_by_key(|(rect, _, _, _)| rect.layer)
+    .map(|(_, _, button, _)| button.id)
+}
+
+pub fn button_under_cursor(world: &World, cursor: &Vector2<f32>) -> Option<u32> {
+  find_button(
+    &world.read_storage::<ScreenRect>(),
+    &world.read_storage::<Transform2D>(),
+    &world.read_storage::<Button>(),
+    &world.read_storage::<Hidden>(),
+    cursor
+  )
+}
+
+// button under cursor for this frame, clicks over buttons must not reach the map
+#[derive(Default)]
+pub struct HoveredButton {
+  pub id: Option<u32>,
+}
+
+impl HoveredButton {
+  pub fn clicked(&self, input: &InputState) -> Option<u32> {
+    if input.is_button_down(MouseButton::Left) {
+      self.id
+    } else {
+      None
+    }
+  }
+}
+
+pub struct ButtonHoverSystem;
+
+impl<'a> System<'a> for ButtonHoverSystem {
+  type SystemData = (
+    Read<'a, InputState>,
+    Write<'a, HoveredButton>,
+    ReadStorage<'a, ScreenRect>,
+    ReadStorage<'a, Transform2D>,
+    ReadStorage<'a, Button>,
+    ReadStorage<'a, Hidden>,
+  );
+
+  fn run(&mut self, (input, mut hovered, rects, transforms, buttons, hidden): Self::SystemData) {
+    hovered.id = find_button(&rects, &transforms, &buttons, &hidden, &input.cursor);
+  }
+}
+
+pub fn clicked_button(world: &World) -> Option<u32> {
+  let input = world.read_resource::<InputState>();
+  if !input.is_button_down(MouseButton::Left) {
+    return None;
+  }
+  button_under_cursor(world, &input.cursor)
+}
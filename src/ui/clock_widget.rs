@@ -0,0 +1,41 @@
+use amethyst::{
+  ecs::{ Component, Join, NullStorage, Read, ReadStorage, System, WriteStorage },
+  prelude::World,
+};
+use crate::simulation::{ Clock, WeatherState };
+use super::text_panel::{ Anchor, TextPanel, create_text_panel };
+
+#[derive(Default)]
+pub struct ClockWidget;
+
+impl Component for ClockWidget {
+  type Storage = NullStorage<Self>;
+}
+
+pub fn create_clock_widget(world: &mut World) {
+  world.register::<ClockWidget>();
+  let panel = create_text_panel(world, Anchor::TopRight, (-170.0, -20.0), (320.0, 30.0));
+  if let Err(e) = world.write_storage::<ClockWidget>().insert(panel, ClockWidget) {
+    println!("cannot create clock widget: {}", e);
+  }
+}
+
+pub struct ClockWidgetSystem;
+
+impl<'a> System<'a> for ClockWidgetSystem {
+  type SystemData = (
+    Read<'a, Clock>,
+    Read<'a, WeatherState>,
+    ReadStorage<'a, ClockWidget>,
+    WriteStorage<'a, TextPanel>,
+  );
+
+  fn run(&mut self, (clock, weather, widgets, mut panels): Self::SystemData) {
+    let text = format!("{}, {:?}", clock.describe(), weather.current);
+    for (_, panel) in (&widgets, &mut panels).join() {
+      if panel.text != text {
+        panel.text = text.clone();
+      }
+    }
+  }
+}
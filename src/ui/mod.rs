@@ -0,0 +1,7 @@
+pub mod speed_widget;
+pub mod button;
+pub mod text_panel;
+pub mod tile_inspector;
+pub mod pawn_inspector;
+pub mod tool_strip;
+pub mod clock_widget;
@@ -0,0 +1,247 @@
+use amethyst::{
+  core::{ Hidden, nalgebra::Vector2 },
+  ecs::{ BitSet, Component, Entities, Entity, EntitiesRes, Join, NullStorage, Read, ReadStorage, System, Write, WriteStorage },
+  prelude::World,
+};
+use winit::MouseButton;
+use crate::cursor::tile_picking_system::PickedTile;
+use crate::game_messages::{ GameMessageResource, GameMessage };
+use crate::input::InputState;
+use crate::pawn::{ Spece, PawnId, Name };
+use crate::rendering::tile_map::Tile;
+use crate::resource::Inventory;
+use crate::simulation::{ Needs, Job, Jobs, Health };
+use crate::states::PauseMenu;
+use super::button::{ create_button, Button, HoveredButton };
+use super::text_panel::{ Anchor, TextPanel, create_text_panel };
+use super::tile_inspector::describe_spece;
+
+const DRAFT_BUTTON: u32 = 100;
+const PRIORITIZE_BUTTON: u32 = 101;
+const INSPECTOR_LAYER: u8 = 5;
+const BAR_LENGTH: usize = 10;
+// panel center and size, bottom left anchored
+const PANEL_POSITION: (f32, f32) = (500.0, 130.0);
+const PANEL_SIZE: (f32, f32) = (320.0, 240.0);
+const BUTTON_SIZE: (f32, f32) = (100.0, 40.0);
+const BUTTON_GAP: f32 = 10.0;
+
+#[derive(Default)]
+pub struct PawnInspector;
+
+impl Component for PawnInspector {
+  type Storage = NullStorage<Self>;
+}
+
+#[derive(Default)]
+pub struct PawnSelection {
+  pub selected: Option<Entity>,
+}
+
+pub fn create_pawn_inspector(world: &mut World) {
+  world.register::<PawnInspector>();
+  world.register::<Hidden>();
+  let panel = create_text_panel(world, Anchor::BottomLeft, PANEL_POSITION, PANEL_SIZE);
+  if let Err(e) = world.write_storage::<PawnInspector>().insert(panel, PawnInspector) {
+    println!("cannot create pawn inspector: {}", e);
+  }
+  // buttons sit in a row on top of the panel, starting at its left edge
+  let size = Vector2::<f32>::new(BUTTON_SIZE.0, BUTTON_SIZE.1);
+  let top_left = Vector2::<f32>::new(
+    PANEL_POSITION.0 - PANEL_SIZE.0 / 2.0,
+    PANEL_POSITION.1 + PANEL_SIZE.1 / 2.0
+  );
+  let buttons = [
+    ("./resources/sprites/ui/button_draft.png", DRAFT_BUTTON),
+    ("./resources/sprites/ui/button_prioritize.png", PRIORITIZE_BUTTON),
+  ];
+  for (i, (texture, id)) in buttons.iter().enumerate() {
+    let at = top_left + size / 2.0 + Vector2::<f32>::new(i as f32 * (size.x + BUTTON_GAP), BUTTON_GAP);
+    let button = create_button(world, texture, at, size, INSPECTOR_LAYER, *id);
+    // shown only when some pawn is selected
+    if let Err(e) = world.write_storage::<Hidden>().insert(button, Hidden) {
+      println!("cannot hide pawn inspector button: {}", e);
+    }
+  }
+}
+
+// Pawn standing on the tile, lowest id wins when there are several.
+// Pawns hidden by fog of war, the `hidden` mask, can not be picked.
+pub fn pawn_on_tile(
+  entities: &EntitiesRes,
+  species: &ReadStorage<Spece>,
+  ids: &ReadStorage<PawnId>,
+  tiles: &ReadStorage<Tile>,
+  hidden: &BitSet,
+  tile: &Tile
+) -> Option<Entity> {
+  (entities, species, ids, tiles, !hidden).join()
+    .filter(|(_, _, _, t, _)| t.position == tile.position)
+    .min_by_key(|(_, _, id, _, _)| id.0)
+    .map(|(e, _, _, _, _)| e)
+}
+
+fn need_bar(name: &str, value: f32) -> String {
+  let filled = (value.max(0.0).min(1.0) * BAR_LENGTH as f32).round() as usize;
+  format!(
+    "{:<7} [{}{}] {:>3}%",
+    name,
+    "#".repeat(filled),
+    ".".repeat(BAR_LENGTH - filled),
+    (value * 100.0).round() as i32
+  )
+}
+
+fn describe_job(job: &Job) -> String {
+  match job {
+    Job::MoveTo(tile) => format!("move to {}, {}, {}", tile.position.x, tile.position.y, tile.position.z),
+    Job::Forage(tile) => format!("forage at {}, {}, {}", tile.position.x, tile.position.y, tile.position.z),
+    Job::Attack(target) => format!("attack #{}", target.0),
+    _ => format!("{:?}", job).to_lowercase(),
+  }
+}
+
+pub struct PawnInspectorSystem;
+
+impl<'a> System<'a> for PawnInspectorSystem {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PauseMenu>,
+    Read<'a, PickedTile>,
+    Read<'a, HoveredButton>,
+    Write<'a, PawnSelection>,
+    Write<'a, GameMessageResource>,
+    Entities<'a>,
+    ReadStorage<'a, Spece>,
+    ReadStorage<'a, PawnId>,
+    ReadStorage<'a, Name>,
+    ReadStorage<'a, Tile>,
+    ReadStorage<'a, Needs>,
+    ReadStorage<'a, Health>,
+    ReadStorage<'a, Inventory>,
+    ReadStorage<'a, Jobs>,
+    ReadStorage<'a, Button>,
+    ReadStorage<'a, PawnInspector>,
+    WriteStorage<'a, Hidden>,
+    WriteStorage<'a, TextPanel>,
+  );
+
+  fn run(&mut self, (
+    input,
+    pause_menu,
+    picked,
+    hovered,
+    mut selection,
+    mut game_messages,
+    entities,
+    species,
+    ids,
+    names,
+    tiles,
+    needs,
+    healths,
+    inventories,
+    jobs,
+    buttons,
+    inspectors,
+    mut hidden,
+    mut panels
+  ): Self::SystemData) {
+    if let Some(s) = selection.selected {
+      if !entities.is_alive(s) || ids.get(s).is_none() || hidden.contains(s) {
+        selection.selected = None;
+      }
+    }
+    // right click on a pawn selects it, on the selected one lets it go,
+    // on a free tile queues a walk there for the selected pawn
+    let right_click = !pause_menu.open && input.is_button_down(MouseButton::Right) && hovered.id.is_none();
+    if let (true, Some(tile)) = (right_click, picked.tile.as_ref()) {
+      match pawn_on_tile(&entities, &species, &ids, &tiles, hidden.mask(), tile) {
+        Some(pawn) if selection.selected == Some(pawn) => selection.selected = None,
+        Some(pawn) => selection.selected = Some(pawn),
+        None => if let Some(id) = selection.selected.and_then(|s| ids.get(s)) {
+          game_messages.messages.push(GameMessage::QueueMoveTo(*id, tile.clone()));
+        }
+      }
+    }
+
+    if let (false, Some(pawn)) = (pause_menu.open, selection.selected) {
+      let id = *ids.get(pawn).unwrap();
+      if let Some(pawn_jobs) = jobs.get(pawn) {
+        match hovered.clicked(&input) {
+          Some(DRAFT_BUTTON) => game_messages.messages.push(GameMessage::SetDrafted(id, !pawn_jobs.drafted)),
+          Some(PRIORITIZE_BUTTON) => game_messages.messages.push(GameMessage::SetPrioritized(id, !pawn_jobs.prioritized)),
+          _ => {}
+        }
+      }
+    }
+
+    let description = match selection.selected {
+      None => String::new(),
+      Some(pawn) => {
+        let mut lines = Vec::new();
+        let name = names.get(pawn).map(|n| n.0.as_str()).unwrap_or("nameless");
+        lines.push(format!("{} (#{})", name, ids.get(pawn).unwrap().0));
+        if let Some(spece) = species.get(pawn) {
+          lines.push(describe_spece(spece));
+        }
+        if let Some(h) = healths.get(pawn) {
+          lines.push(need_bar("health", h.0));
+        }
+        if let Some(n) = needs.get(pawn) {
+          lines.push(need_bar("hunger", n.hunger));
+          lines.push(need_bar("rest", n.rest));
+          lines.push(need_bar("warmth", n.warmth));
+        }
+        match inventories.get(pawn) {
+          Some(inventory) if !inventory.items.is_empty() => {
+            for stack in inventory.items.iter() {
+              lines.push(format!("carries {:?}: {}", stack.resource, stack.count));
+            }
+          }
+          _ => lines.push("carries nothing".to_string()),
+        }
+        if let Some(j) = jobs.get(pawn) {
+          let mut flags = Vec::new();
+          if j.drafted {
+            flags.push("drafted");
+          }
+          if j.prioritized {
+            flags.push("prioritized");
+          }
+          if !flags.is_empty() {
+            lines.push(flags.join(", "));
+          }
+          match &j.current {
+            None => lines.push("job: idle".to_string()),
+            Some(job) => lines.push(format!("job: {}", describe_job(job))),
+          }
+          for job in j.queue.iter() {
+            lines.push(format!("  then: {}", describe_job(job)));
+          }
+        }
+        lines.join("\n")
+      }
+    };
+    for (_, panel) in (&inspectors, &mut panels).join() {
+      if panel.text != description {
+        panel.text = description.clone();
+      }
+    }
+
+    let inspector_buttons: Vec<Entity> = (&entities, &buttons).join()
+      .filter(|(_, b)| b.id == DRAFT_BUTTON || b.id == PRIORITIZE_BUTTON)
+      .map(|(e, _)| e)
+      .collect();
+    for button in inspector_buttons {
+      let visible = selection.selected.is_some();
+      if visible && hidden.contains(button) {
+        hidden.remove(button);
+      } else if !visible && !hidden.contains(button) {
+        if let Err(e) = hidden.insert(button, Hidden) {
+          println!("cannot hide pawn inspector button: {}", e);
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,65 @@
+use amethyst::{
+  core::nalgebra::{ Vector2 },
+  ecs::{ Component, DenseVecStorage, Join, Read, ReadStorage, System, WriteStorage },
+  prelude::{ Builder, World },
+  renderer::{ ScreenDimensions, TextureHandle },
+};
+use nalgebra_glm::{ translation2d };
+use crate::asset_loader::load_png_texture;
+use crate::rendering::screen_space::screen_rect::{ ScreenRect, Transform2D };
+use crate::simulation::GameSpeed;
+
+const WIDGET_SIZE: f32 = 48.0;
+const WIDGET_MARGIN: f32 = 16.0;
+
+pub struct SpeedWidget {
+  // one texture per speed, indexed by GameSpeed::index
+  textures: Vec<TextureHandle>,
+}
+
+impl Component for SpeedWidget {
+  type Storage = DenseVecStorage<Self>;
+}
+
+pub fn create_speed_widget(world: &mut World) {
+  world.register::<ScreenRect>();
+  world.register::<Transform2D>();
+  world.register::<SpeedWidget>();
+  let textures: Vec<TextureHandle> = (0..4)
+    .map(|i| load_png_texture(world, format!("./resources/sprites/ui/speed_{}.png", i)))
+    .collect();
+  let mut rect: ScreenRect = Default::default();
+  rect.size = Vector2::<f32>::new(WIDGET_SIZE, WIDGET_SIZE);
+  world.create_entity()
+    .with(rect)
+    .with(textures[GameSpeed::default().index()].clone())
+    .with(Transform2D::default())
+    .with(SpeedWidget { textures })
+    .build();
+}
+
+pub struct SpeedWidgetSystem;
+
+impl<'a> System<'a> for SpeedWidgetSystem {
+  type SystemData = (
+    Read<'a, GameSpeed>,
+    Option<Read<'a, ScreenDimensions>>,
+    ReadStorage<'a, SpeedWidget>,
+    WriteStorage<'a, TextureHandle>,
+    WriteStorage<'a, Transform2D>,
+  );
+
+  fn run(&mut self, (speed, screen_dim, widgets, mut textures, mut transforms): Self::SystemData) {
+    let dimensions = match screen_dim {
+      None => return,
+      Some(sd) => Vector2::<f32>::new(sd.width(), sd.height())
+    };
+    let corner = dimensions - Vector2::<f32>::new(WIDGET_SIZE + WIDGET_MARGIN, WIDGET_SIZE + WIDGET_MARGIN);
+    for (widget, texture, transform) in (&widgets, &mut textures, &mut transforms).join() {
+      if let Some(t) = widget.textures.get(speed.index()) {
+        *texture = t.clone();
+      }
+      transform.model = translation2d(&corner);
+    }
+  }
+}
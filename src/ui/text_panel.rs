@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::mem;
+use amethyst::{
+  core::{ Hidden, nalgebra::Vector2 },
+  ecs::{ Component, DenseVecStorage, Entities, Entity, Join, Read, ReadStorage, System, WriteStorage },
+  prelude::{ Builder, World },
+  renderer::{ ScreenDimensions, TextureHandle },
+};
+use nalgebra_glm::translation2d;
+use crate::asset_loader::load_png_texture;
+use crate::rendering::screen_space::screen_rect::{ ScreenRect, Transform2D };
+
+// text is drawn above inspectors and below menus
+pub const TEXT_LAYER: u8 = 8;
+const GLYPH_WIDTH: f32 = 8.0;
+const GLYPH_HEIGHT: f32 = 16.0;
+// glyphs are shipped for printable ASCII and the degree sign, the rest is drawn as '?'
+const FONT_DIR: &str = "./resources/sprites/ui/font";
+const EXTRA_GLYPHS: [char; 1] = ['°'];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+  TopLeft,
+  TopRight,
+  BottomLeft,
+  BottomRight,
+  Middle,
+}
+
+// Multi-line text block drawn by ScreenSpacePass, one rect per glyph.
+// Position is the offset of the panel center from the anchor, in pixels.
+pub struct TextPanel {
+  pub text: String,
+  pub anchor: Anchor,
+  pub position: Vector2<f32>,
+  pub size: Vector2<f32>,
+}
+
+impl Component for TextPanel {
+  type Storage = DenseVecStorage<Self>;
+}
+
+pub struct Font {
+  glyphs: HashMap<char, TextureHandle>,
+}
+
+impl Font {
+  fn glyph(&self, c: char) -> Option<&TextureHandle> {
+    self.glyphs.get(&c).or_else(|| self.glyphs.get(&'?'))
+  }
+}
+
+fn load_font(world: &mut World) {
+  if world.res.has_value::<Font>() {
+    return;
+  }
+  let chars: Vec<char> = (32u8..=126).map(|b| b as char).chain(EXTRA_GLYPHS.iter().cloned()).collect();
+  let glyphs = chars.into_iter()
+    .map(|c| (c, load_png_texture(world, format!("{}/{}.png", FONT_DIR, c as u32))))
+    .collect();
+  world.add_resource(Font { glyphs });
+}
+
+pub fn create_text_panel(
+  world: &mut World,
+  anchor: Anchor,
+  position: (f32, f32),
+  size: (f32, f32)
+) -> Entity {
+  world.register::<TextPanel>();
+  load_font(world);
+  world.create_entity()
+    .with(TextPanel {
+      text: String::new(),
+      anchor,
+      position: Vector2::<f32>::new(position.0, position.1),
+      size: Vector2::<f32>::new(size.0, size.1),
+    })
+    .build()
+}
+
+// Breaks text into lines fitting `columns`, on spaces where possible.
+fn wrap(text: &str, columns: usize) -> Vec<Vec<char>> {
+  let mut lines = Vec::new();
+  if columns == 0 {
+    return lines;
+  }
+  for paragraph in text.split('\n') {
+    let mut line: Vec<char> = Vec::new();
+    for (i, word) in paragraph.split(' ').enumerate() {
+      let word: Vec<char> = word.chars().collect();
+      if i > 0 {
+        if !line.is_empty() && line.len() + 1 + word.len() > columns {
+          lines.push(mem::replace(&mut line, Vec::new()));
+        } else {
+          line.push(' ');
+        }
+      }
+      for c in word {
+        if line.len() >= columns {
+          lines.push(mem::replace(&mut line, Vec::new()));
+        }
+        line.push(c);
+      }
+    }
+    lines.push(line);
+  }
+  lines
+}
+
+fn anchor_point(anchor: Anchor, screen: Vector2<f32>) -> Vector2<f32> {
+  match anchor {
+    Anchor::TopLeft => Vector2::<f32>::new(0.0, screen.y),
+    Anchor::TopRight => screen,
+    Anchor::BottomLeft => Vector2::<f32>::new(0.0, 0.0),
+    Anchor::BottomRight => Vector2::<f32>::new(screen.x, 0.0),
+    Anchor::Middle => screen / 2.0,
+  }
+}
+
+struct Layout {
+  text: String,
+  corner: Vector2<f32>,
+  glyphs: Vec<Entity>,
+}
+
+// Lays out glyph rects of text panels whenever their text or place changes.
+// Glyph entities are kept per panel and reused, the ones of deleted panels are deleted too.
+#[derive(Default)]
+pub struct TextPanelSystem {
+  layouts: HashMap<Entity, Layout>,
+}
+
+impl<'a> System<'a> for TextPanelSystem {
+  type SystemData = (
+    Entities<'a>,
+    Option<Read<'a, ScreenDimensions>>,
+    Option<Read<'a, Font>>,
+    ReadStorage<'a, TextPanel>,
+    ReadStorage<'a, Hidden>,
+    WriteStorage<'a, ScreenRect>,
+    WriteStorage<'a, TextureHandle>,
+    WriteStorage<'a, Transform2D>,
+  );
+
+  fn run(&mut self, (entities, screen_dimensions, font, panels, hidden, mut rects, mut textures, mut transforms): Self::SystemData) {
+    let gone: Vec<Entity> = self.layouts.keys()
+      .filter(|e| !entities.is_alive(**e) || !panels.contains(**e))
+      .cloned()
+      .collect();
+    for panel in gone {
+      if let Some(layout) = self.layouts.remove(&panel) {
+        for glyph in layout.glyphs {
+          if let Err(e) = entities.delete(glyph) {
+            println!("cannot delete glyph: {}", e);
+          }
+        }
+      }
+    }
+    let font = match font {
+      None => return,
+      Some(f) => f
+    };
+    let screen = match screen_dimensions {
+      None => Vector2::<f32>::new(0.0, 0.0),
+      Some(sd) => Vector2::<f32>::new(sd.width(), sd.height())
+    };
+    for (entity, panel) in (&entities, &panels).join() {
+      let text = if hidden.contains(entity) { "" } else { panel.text.as_str() };
+      let center = anchor_point(panel.anchor, screen) + panel.position;
+      let corner = center + Vector2::<f32>::new(-panel.size.x, panel.size.y) / 2.0;
+      let layout = self.layouts.entry(entity).or_insert_with(|| Layout {
+        text: String::new(),
+        corner,
+        glyphs: Vec::new(),
+      });
+      if layout.text == text && layout.corner == corner {
+        continue;
+      }
+      layout.text = text.to_string();
+      layout.corner = corner;
+
+      let columns = (panel.size.x / GLYPH_WIDTH) as usize;
+      let rows = (panel.size.y / GLYPH_HEIGHT) as usize;
+      let cells: Vec<(char, usize, usize)> = wrap(text, columns).into_iter()
+        .take(rows)
+        .enumerate()
+        .flat_map(|(row, line)| line.into_iter()
+          .enumerate()
+          .filter(|(_, c)| *c != ' ')
+          .map(move |(column, c)| (c, column, row)))
+        .collect();
+      while layout.glyphs.len() < cells.len() {
+        let mut rect: ScreenRect = Default::default();
+        rect.size = Vector2::<f32>::new(GLYPH_WIDTH, GLYPH_HEIGHT);
+        rect.layer = TEXT_LAYER;
+        let glyph = entities.build_entity()
+          .with(rect, &mut rects)
+          .with(Transform2D::default(), &mut transforms)
+          .build();
+        layout.glyphs.push(glyph);
+      }
+      for (i, glyph) in layout.glyphs.iter().enumerate() {
+        // glyphs left over from a longer text lose their texture, so they are not drawn
+        let cell = cells.get(i).and_then(|(c, column, row)| font.glyph(*c).map(|t| (t, *column, *row)));
+        match cell {
+          None => {
+            textures.remove(*glyph);
+          }
+          Some((texture, column, row)) => {
+            let at = corner + Vector2::<f32>::new(
+              column as f32 * GLYPH_WIDTH,
+              -((row + 1) as f32) * GLYPH_HEIGHT
+            );
+            if let Some(transform) = transforms.get_mut(*glyph) {
+              transform.model = translation2d(&at);
+            }
+            if let Err(e) = textures.insert(*glyph, texture.clone()) {
+              println!("cannot draw glyph: {}", e);
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn lines(text: &str, columns: usize) -> Vec<String> {
+    wrap(text, columns).into_iter().map(|l| l.into_iter().collect()).collect()
+  }
+
+  #[test]
+  fn wraps_on_spaces_and_keeps_line_breaks() {
+    assert_eq!(lines("hunger 0.50\nrest 0.25", 20), vec!["hunger 0.50", "rest 0.25"]);
+    assert_eq!(lines("scenario forest is loaded", 12), vec!["scenario", "forest is", "loaded"]);
+  }
+
+  #[test]
+  fn cuts_words_longer_than_a_line() {
+    assert_eq!(lines("./resources/scenarios", 10), vec!["./resource", "s/scenario", "s"]);
+    assert_eq!(lines("  indented", 20), vec!["  indented"]);
+  }
+}
@@ -0,0 +1,183 @@
+use amethyst::{
+  core::Hidden,
+  ecs::{ Component, Entities, Entity, Join, NullStorage, Read, ReadStorage, System, Write, WriteStorage },
+  prelude::World,
+};
+use winit::MouseButton;
+use crate::cursor::tile_picking_system::PickedTile;
+use crate::game_state::TileMapTilesKind;
+use crate::input::InputState;
+use crate::pawn::{ Spece, PawnId, Name, PLAYER_FACTION };
+use crate::rendering::tile_map::{ Tile, TileMap, Elevation };
+use crate::resource::{ ResourceDictionary, ResourceStack };
+use crate::simulation::{ terrain_cost, line_of_sight, Visibility, TileVisibility, Fire, Plant, GrowthStage, Temperature };
+use crate::states::PauseMenu;
+use super::button::HoveredButton;
+use super::pawn_inspector::{ PawnSelection, pawn_on_tile };
+use super::text_panel::{ Anchor, TextPanel, create_text_panel };
+
+#[derive(Default)]
+pub struct TileInspector;
+
+impl Component for TileInspector {
+  type Storage = NullStorage<Self>;
+}
+
+// tile pinned with right click, inspector shows hovered tile otherwise.
+// Right clicks on pawns, or while a pawn is selected, belong to the pawn inspector.
+#[derive(Default)]
+pub struct TileSelection {
+  pub selected: Option<Entity>,
+}
+
+pub fn create_tile_inspector(world: &mut World) {
+  world.register::<TileInspector>();
+  let panel = create_text_panel(world, Anchor::BottomLeft, (170.0, 130.0), (320.0, 240.0));
+  if let Err(e) = world.write_storage::<TileInspector>().insert(panel, TileInspector) {
+    println!("cannot create tile inspector: {}", e);
+  }
+}
+
+pub struct TileInspectorSystem;
+
+impl<'a> System<'a> for TileInspectorSystem {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PauseMenu>,
+    Read<'a, PickedTile>,
+    Read<'a, HoveredButton>,
+    Read<'a, PawnSelection>,
+    Write<'a, TileSelection>,
+    Read<'a, ResourceDictionary>,
+    Read<'a, Visibility>,
+    Read<'a, Temperature>,
+    Entities<'a>,
+    ReadStorage<'a, Tile>,
+    ReadStorage<'a, TileMapTilesKind>,
+    ReadStorage<'a, Elevation>,
+    ReadStorage<'a, TileMap>,
+    ReadStorage<'a, Name>,
+    ReadStorage<'a, Spece>,
+    ReadStorage<'a, PawnId>,
+    ReadStorage<'a, ResourceStack>,
+    ReadStorage<'a, Fire>,
+    ReadStorage<'a, Plant>,
+    ReadStorage<'a, Hidden>,
+    ReadStorage<'a, TileInspector>,
+    WriteStorage<'a, TextPanel>,
+  );
+
+  fn run(&mut self, (
+    input,
+    pause_menu,
+    picked,
+    hovered,
+    pawn_selection,
+    mut selection,
+    dictionary,
+    visibility,
+    temperature,
+    entities,
+    tiles,
+    kinds,
+    elevations,
+    maps,
+    names,
+    species,
+    ids,
+    stacks,
+    fires,
+    plants,
+    hidden,
+    inspectors,
+    mut panels
+  ): Self::SystemData) {
+    let pawn_clicked = picked.tile.as_ref()
+      .and_then(|t| pawn_on_tile(&entities, &species, &ids, &tiles, hidden.mask(), t))
+      .is_some();
+    if !pause_menu.open && input.is_button_down(MouseButton::Right) && hovered.id.is_none()
+      && pawn_selection.selected.is_none() && !pawn_clicked {
+      selection.selected = match (selection.selected, picked.entity) {
+        (Some(s), Some(p)) if s == p => None,
+        (_, p) => p
+      };
+    }
+    if let Some(s) = selection.selected {
+      if !entities.is_alive(s) {
+        selection.selected = None;
+      }
+    }
+    let target = selection.selected.or(picked.entity);
+    let description = match target.and_then(|e| tiles.get(e).map(|t| (t, kinds.get(e)))) {
+      None => String::new(),
+      Some((tile, _)) if visibility.of(PLAYER_FACTION, tile) == TileVisibility::Unexplored => {
+        "unexplored".to_string()
+      }
+      Some((tile, kind)) => {
+        let mut lines = Vec::new();
+        let pinned = if selection.selected.is_some() { " (pinned)" } else { "" };
+        let p = tile.position;
+        lines.push(format!("tile {}, {}, {}{}", p.x, p.y, p.z, pinned));
+        let elevation_of = |t: &Tile| (&tiles, &elevations).join()
+          .find(|(other, _)| other.position == t.position)
+          .map(|(_, e)| e.0);
+        lines.push(format!("elevation: {}", elevation_of(tile).unwrap_or(0)));
+        if let Some(t) = temperature.at(tile) {
+          lines.push(format!("temperature: {:.1}°C", t));
+        }
+        let observer = pawn_selection.selected.and_then(|e| tiles.get(e).map(|t| (t, names.get(e))));
+        if let (Some((from, name)), Some(tm)) = (observer, (&maps).join().next()) {
+          let seen = line_of_sight(tm.topology, from, tile, &elevation_of);
+          let name = name.map(|n| n.0.as_str()).unwrap_or("selected pawn");
+          lines.push(format!("seen by {}: {}", name, if seen { "yes" } else { "no" }));
+        }
+        match kind {
+          None => lines.push("terrain: unknown".to_string()),
+          Some(kind) => {
+            lines.push(format!("terrain: {:?}", kind));
+            match terrain_cost(*kind) {
+              None => lines.push("movement: impassable".to_string()),
+              Some(cost) => lines.push(format!("movement cost: {:.1}", cost)),
+            }
+          }
+        }
+        for (spece, _, _) in (&species, &tiles, !&hidden).join().filter(|(_, t, _)| t.position == p) {
+          lines.push(format!("pawn: {}", describe_spece(spece)));
+        }
+        for (fire, _) in (&fires, &tiles).join().filter(|(_, t)| t.position == p) {
+          lines.push(format!("fire: {:?}", fire));
+        }
+        for (plant, _) in (&plants, &tiles).join().filter(|(_, t)| t.position == p) {
+          match plant.stage {
+            GrowthStage::Mature => lines.push(format!(
+              "{:?}: {} / {} fruit", plant.kind, plant.fruit, plant.kind.max_fruit()
+            )),
+            stage => lines.push(format!("{:?}: {:?}", plant.kind, stage)),
+          }
+        }
+        for (stack, _) in (&stacks, &tiles).join().filter(|(_, t)| t.position == p) {
+          match dictionary.get(&stack.resource) {
+            None => lines.push(format!("{:?}: {}", stack.resource, stack.count)),
+            Some(info) => lines.push(format!(
+              "{:?}: {} / {}",
+              stack.resource, stack.count, info.stacking.unordered
+            )),
+          }
+        }
+        lines.join("\n")
+      }
+    };
+    for (_, panel) in (&inspectors, &mut panels).join() {
+      if panel.text != description {
+        panel.text = description.clone();
+      }
+    }
+  }
+}
+
+pub fn describe_spece(spece: &Spece) -> String {
+  match spece {
+    Spece::Wolf(sex) => format!("wolf, {:?}", sex),
+    Spece::Human(sex, race, complex) => format!("human, {:?}, {:?}, {:?}", sex, race, complex),
+  }
+}
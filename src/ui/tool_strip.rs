@@ -0,0 +1,77 @@
+use amethyst::{
+  core::nalgebra::Vector2,
+  ecs::{ Component, Join, NullStorage, Read, ReadStorage, System, Write, WriteStorage },
+  prelude::World,
+};
+use crate::input::InputState;
+use crate::states::PauseMenu;
+use crate::tools::{ Tool, ToolPalette, TOOLS };
+use super::button::{ create_button, HoveredButton };
+use super::text_panel::{ Anchor, TextPanel, create_text_panel };
+
+const FIRST_TOOL_BUTTON: u32 = 200;
+const STRIP_LAYER: u8 = 5;
+const BUTTON_SIZE: f32 = 48.0;
+const BUTTON_SPACING: f32 = 56.0;
+const STRIP_X: f32 = 720.0;
+const STRIP_Y: f32 = 34.0;
+
+#[derive(Default)]
+pub struct ToolStatus;
+
+impl Component for ToolStatus {
+  type Storage = NullStorage<Self>;
+}
+
+fn texture(tool: Tool) -> &'static str {
+  match tool {
+    Tool::PlacePawn => "./resources/sprites/ui/tool_pawn.png",
+    Tool::PlaceResource => "./resources/sprites/ui/tool_resource.png",
+    Tool::PaintTerrain => "./resources/sprites/ui/tool_terrain.png",
+    Tool::Erase => "./resources/sprites/ui/tool_erase.png",
+    Tool::Measure => "./resources/sprites/ui/tool_measure.png",
+    Tool::PlaceFire => "./resources/sprites/ui/tool_fire.png",
+  }
+}
+
+pub fn create_tool_strip(world: &mut World) {
+  world.register::<ToolStatus>();
+  let size = Vector2::<f32>::new(BUTTON_SIZE, BUTTON_SIZE);
+  for (i, tool) in TOOLS.iter().enumerate() {
+    let at = Vector2::<f32>::new(STRIP_X + i as f32 * BUTTON_SPACING, STRIP_Y);
+    create_button(world, texture(*tool), at, size, STRIP_LAYER, FIRST_TOOL_BUTTON + i as u32);
+  }
+  let status = create_text_panel(world, Anchor::BottomLeft, (836.0, 90.0), (280.0, 40.0));
+  if let Err(e) = world.write_storage::<ToolStatus>().insert(status, ToolStatus) {
+    println!("cannot create tool status: {}", e);
+  }
+}
+
+pub struct ToolStripSystem;
+
+impl<'a> System<'a> for ToolStripSystem {
+  type SystemData = (
+    Read<'a, InputState>,
+    Read<'a, PauseMenu>,
+    Read<'a, HoveredButton>,
+    Write<'a, ToolPalette>,
+    ReadStorage<'a, ToolStatus>,
+    WriteStorage<'a, TextPanel>,
+  );
+
+  fn run(&mut self, (input, pause_menu, hovered, mut palette, statuses, mut panels): Self::SystemData) {
+    if let (false, Some(id)) = (pause_menu.open, hovered.clicked(&input)) {
+      if id >= FIRST_TOOL_BUTTON {
+        if let Some(tool) = TOOLS.get((id - FIRST_TOOL_BUTTON) as usize) {
+          palette.select(*tool);
+        }
+      }
+    }
+    let description = palette.describe();
+    for (_, panel) in (&statuses, &mut panels).join() {
+      if panel.text != description {
+        panel.text = description.clone();
+      }
+    }
+  }
+}